@@ -0,0 +1,263 @@
+//! Apache Arrow 导出（需启用 `arrow` feature）
+//!
+//! 将记录转换为列式 [`RecordBatch`]，供 DataFusion、Polars 等直接使用。
+//! 用户名、应用名、标签等低基数列采用字典编码。
+
+use std::sync::{Arc, LazyLock};
+
+use arrow_array::builder::{
+    Float32Builder, Int64Builder, StringBuilder, StringDictionaryBuilder,
+    TimestampMillisecondBuilder, UInt8Builder, UInt32Builder,
+};
+use arrow_array::types::Int32Type;
+use arrow_array::{ArrayRef, RecordBatch};
+use arrow_schema::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use rayon::prelude::*;
+
+use crate::error::ParseError;
+use crate::parser::{LogIterator, LogParser};
+use crate::sqllog::Sqllog;
+
+static SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    let dict = || DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8));
+    Arc::new(Schema::new(vec![
+        Field::new(
+            "ts",
+            DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into())),
+            true,
+        ),
+        Field::new("ep", DataType::UInt8, false),
+        Field::new("sess_id", DataType::Utf8, false),
+        Field::new("thrd_id", DataType::Utf8, false),
+        Field::new("username", dict(), false),
+        Field::new("trxid", DataType::Utf8, false),
+        Field::new("statement", DataType::Utf8, false),
+        Field::new("appname", dict(), false),
+        Field::new("client_ip", DataType::Utf8, true),
+        Field::new("tag", dict(), true),
+        Field::new("sql", DataType::Utf8, false),
+        Field::new("exectime", DataType::Float32, true),
+        Field::new("rowcount", DataType::UInt32, true),
+        Field::new("exec_id", DataType::Int64, true),
+    ]))
+});
+
+/// 导出 `RecordBatch` 的固定 schema
+///
+/// | 列 | 类型 | 说明 |
+/// |----|------|------|
+/// | `ts` | `Timestamp(ms, "UTC")` | 按解析器 `utc_offset` 换算；字段越界时为 null |
+/// | `ep` | `UInt8` | |
+/// | `sess_id` / `thrd_id` / `trxid` / `statement` | `Utf8` | |
+/// | `username` / `appname` | `Dictionary(Int32, Utf8)` | |
+/// | `client_ip` | `Utf8` | 记录中无 `ip` 字段时为 null |
+/// | `tag` | `Dictionary(Int32, Utf8)` | 无标签时为 null |
+/// | `sql` | `Utf8` | ORA 记录已去除 `": "` 前缀 |
+/// | `exectime` / `rowcount` / `exec_id` | `Float32` / `UInt32` / `Int64` | 无性能指标时为 null |
+pub fn sqllog_schema() -> SchemaRef {
+    SCHEMA.clone()
+}
+
+/// 逐条追加记录、按需产出 [`RecordBatch`] 的构建器
+pub struct ArrowBatchBuilder {
+    len: usize,
+    ts: TimestampMillisecondBuilder,
+    ep: UInt8Builder,
+    sess_id: StringBuilder,
+    thrd_id: StringBuilder,
+    username: StringDictionaryBuilder<Int32Type>,
+    trxid: StringBuilder,
+    statement: StringBuilder,
+    appname: StringDictionaryBuilder<Int32Type>,
+    client_ip: StringBuilder,
+    tag: StringDictionaryBuilder<Int32Type>,
+    sql: StringBuilder,
+    exectime: Float32Builder,
+    rowcount: UInt32Builder,
+    exec_id: Int64Builder,
+}
+
+impl Default for ArrowBatchBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ArrowBatchBuilder {
+    pub fn new() -> Self {
+        Self {
+            len: 0,
+            ts: TimestampMillisecondBuilder::new().with_timezone("UTC"),
+            ep: UInt8Builder::new(),
+            sess_id: StringBuilder::new(),
+            thrd_id: StringBuilder::new(),
+            username: StringDictionaryBuilder::new(),
+            trxid: StringBuilder::new(),
+            statement: StringBuilder::new(),
+            appname: StringDictionaryBuilder::new(),
+            client_ip: StringBuilder::new(),
+            tag: StringDictionaryBuilder::new(),
+            sql: StringBuilder::new(),
+            exectime: Float32Builder::new(),
+            rowcount: UInt32Builder::new(),
+            exec_id: Int64Builder::new(),
+        }
+    }
+
+    /// 已追加、尚未 [`finish`](Self::finish) 的行数
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// 是否没有待产出的行
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// 追加一条记录
+    pub fn push(&mut self, record: &Sqllog<'_>) {
+        let meta = record.parse_meta();
+        let (sql, indicators) = record.sql_and_indicators();
+
+        self.ts.append_option(record.timestamp_millis());
+        self.ep.append_value(meta.ep);
+        self.sess_id.append_value(&meta.sess_id);
+        self.thrd_id.append_value(&meta.thrd_id);
+        self.username.append_value(&meta.username);
+        self.trxid.append_value(&meta.trxid);
+        self.statement.append_value(&meta.statement);
+        self.appname.append_value(&meta.appname);
+        self.client_ip
+            .append_option((!meta.client_ip.is_empty()).then_some(&meta.client_ip));
+        self.tag.append_option(record.tag.as_deref());
+        self.sql.append_value(&sql);
+        self.exectime
+            .append_option(indicators.as_ref().map(|pm| pm.exectime));
+        self.rowcount
+            .append_option(indicators.as_ref().map(|pm| pm.rowcount));
+        self.exec_id
+            .append_option(indicators.as_ref().map(|pm| pm.exec_id));
+        self.len += 1;
+    }
+
+    /// 产出已追加的行并重置构建器
+    pub fn finish(&mut self) -> Result<RecordBatch, ParseError> {
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(self.ts.finish()),
+            Arc::new(self.ep.finish()),
+            Arc::new(self.sess_id.finish()),
+            Arc::new(self.thrd_id.finish()),
+            Arc::new(self.username.finish()),
+            Arc::new(self.trxid.finish()),
+            Arc::new(self.statement.finish()),
+            Arc::new(self.appname.finish()),
+            Arc::new(self.client_ip.finish()),
+            Arc::new(self.tag.finish()),
+            Arc::new(self.sql.finish()),
+            Arc::new(self.exectime.finish()),
+            Arc::new(self.rowcount.finish()),
+            Arc::new(self.exec_id.finish()),
+        ];
+        self.len = 0;
+        RecordBatch::try_new(sqllog_schema(), columns)
+            .map_err(|e| ParseError::ExportError(e.to_string()))
+    }
+}
+
+/// [`LogParser::arrow_batches`] 返回的迭代器
+///
+/// 按文件顺序产出最多 `batch_size` 行的 `RecordBatch`；解析失败的记录以
+/// `Err` 原位产出，不影响前后批次。
+pub struct ArrowBatches<'a> {
+    inner: LogIterator<'a>,
+    batch_size: usize,
+    builder: ArrowBatchBuilder,
+    done: bool,
+}
+
+impl Iterator for ArrowBatches<'_> {
+    type Item = Result<RecordBatch, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        while self.builder.len() < self.batch_size {
+            match self.inner.next() {
+                Some(Ok(record)) => self.builder.push(&record),
+                Some(Err(e)) => return Some(Err(e)),
+                None => {
+                    self.done = true;
+                    break;
+                }
+            }
+        }
+        if self.builder.is_empty() {
+            return None;
+        }
+        Some(self.builder.finish())
+    }
+}
+
+/// 并行构建时每个 Rayon 分片的累加状态
+struct Accumulator {
+    batch_size: usize,
+    builder: ArrowBatchBuilder,
+    out: Vec<Result<RecordBatch, ParseError>>,
+}
+
+impl Accumulator {
+    fn push(mut self, item: Result<Sqllog<'_>, ParseError>) -> Self {
+        match item {
+            Ok(record) => {
+                self.builder.push(&record);
+                if self.builder.len() >= self.batch_size {
+                    self.out.push(self.builder.finish());
+                }
+            }
+            Err(e) => self.out.push(Err(e)),
+        }
+        self
+    }
+
+    fn finish(mut self) -> Vec<Result<RecordBatch, ParseError>> {
+        if !self.builder.is_empty() {
+            self.out.push(self.builder.finish());
+        }
+        self.out
+    }
+}
+
+impl LogParser {
+    /// 按文件顺序把记录转换为 Arrow `RecordBatch`，每批最多 `batch_size` 行
+    /// （需启用 `arrow` feature）。schema 见 [`sqllog_schema`]。
+    pub fn arrow_batches(&self, batch_size: usize) -> ArrowBatches<'_> {
+        ArrowBatches {
+            inner: self.iter(),
+            batch_size: batch_size.max(1),
+            builder: ArrowBatchBuilder::new(),
+            done: false,
+        }
+    }
+
+    /// 基于 [`par_iter`](Self::par_iter) 并行构建 `RecordBatch`（需启用 `arrow` feature）。
+    ///
+    /// 每个 Rayon 分片独立累积，批次之间的顺序不确定，批内行数不超过 `batch_size`；
+    /// 需要保持文件顺序时使用 [`arrow_batches`](Self::arrow_batches)。
+    pub fn par_arrow_batches(
+        &self,
+        batch_size: usize,
+    ) -> impl ParallelIterator<Item = Result<RecordBatch, ParseError>> + '_ {
+        let batch_size = batch_size.max(1);
+        self.par_iter()
+            .fold(
+                move || Accumulator {
+                    batch_size,
+                    builder: ArrowBatchBuilder::new(),
+                    out: Vec::new(),
+                },
+                Accumulator::push,
+            )
+            .flat_map_iter(Accumulator::finish)
+    }
+}
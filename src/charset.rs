@@ -0,0 +1,313 @@
+//! 文件编码选项与探测
+//!
+//! DM sqllog 的编码取决于实例的 `CHARSET` 设置，通常为 UTF-8 或 GB18030；
+//! 部分站点的客户端使用 GBK、Big5，或以 UTF-16 导出日志。
+
+use encoding::all::{BIG5_2003, GB18030, GBK, UTF_16BE, UTF_16LE};
+use encoding::{DecoderTrap, EncodingRef};
+use simdutf8::basic::from_utf8 as simd_from_utf8;
+use simdutf8::compat::from_utf8 as simd_from_utf8_compat;
+
+use crate::parser::FileEncodingHint;
+
+/// 编码判定时用于打分的采样大小
+const SCORE_SAMPLE: usize = 64 * 1024;
+
+/// 日志文件编码
+///
+/// 通过 [`LogParserBuilder::encoding`](crate::LogParserBuilder::encoding) 设置。
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub enum Encoding {
+    /// 采样文件头 64 KB 与尾部 4 KB 自动判断（默认）。
+    ///
+    /// 启动开销极小，但仅出现在文件中部的非 UTF-8 内容会被误判为 UTF-8：
+    /// 此时采样区外的 meta、tag 与正文逐段校验，非法字节替换为 U+FFFD，
+    /// 并按 [`DecodePolicy`] 报告或返回错误。
+    #[default]
+    AutoSampled,
+    /// 用 `simdutf8` 扫描整个文件后判断：全部为合法 UTF-8 则按 UTF-8，
+    /// 否则在候选编码中选择（默认 GB18030）。
+    AutoFullScan,
+    /// 强制按 UTF-8 解析。
+    ///
+    /// 构建时仍会对整个文件做一次 `simdutf8` 校验；若存在非法字节，则退化为逐段校验：
+    /// meta、tag 与正文中合法的部分零拷贝借用，非法字节一律替换为 U+FFFD（不回退到遗留编码）。
+    Utf8,
+    /// 强制按 GB18030 解码。
+    Gb18030,
+    /// 强制按 GBK 解码。
+    Gbk,
+    /// 强制按 GB2312 解码（以其超集 GBK 解码）。
+    Gb2312,
+    /// 强制按 Big5（Big5-2003）解码。
+    Big5,
+    /// 强制按 UTF-16LE 解码；构建时整体转码为 UTF-8。
+    Utf16Le,
+    /// 强制按 UTF-16BE 解码；构建时整体转码为 UTF-8。
+    Utf16Be,
+    /// 逐条记录判定：每条记录独立用 `simdutf8` 校验，合法则按 UTF-8，否则按 GB18030。
+    ///
+    /// 适用于 `CHARSET` 迁移后新旧编码记录混杂在同一文件中的情况。
+    PerRecord,
+}
+
+impl Encoding {
+    /// 单字节/多字节遗留编码对应的 hint；非遗留编码返回 `None`
+    fn legacy_hint(self) -> Option<FileEncodingHint> {
+        match self {
+            Encoding::Gb18030 => Some(FileEncodingHint::Gb18030),
+            Encoding::Gbk | Encoding::Gb2312 => Some(FileEncodingHint::Gbk),
+            Encoding::Big5 => Some(FileEncodingHint::Big5),
+            _ => None,
+        }
+    }
+
+    /// 若输入应按 UTF-16 处理，返回其解码器。
+    ///
+    /// 自动模式下按 BOM 或"ASCII 字符间隔 0 字节"的特征识别；
+    /// UTF-8 / 遗留编码的 sqllog 中不会出现 0 字节，因此不会误判。
+    fn utf16(self, data: &[u8]) -> Option<EncodingRef> {
+        match self {
+            Encoding::Utf16Le => Some(UTF_16LE),
+            Encoding::Utf16Be => Some(UTF_16BE),
+            Encoding::AutoSampled | Encoding::AutoFullScan => {
+                if data.starts_with(&[0xFF, 0xFE]) {
+                    Some(UTF_16LE)
+                } else if data.starts_with(&[0xFE, 0xFF]) {
+                    Some(UTF_16BE)
+                } else if data.len() >= 4 && data[0] != 0 && data[1] == 0 && data[3] == 0 {
+                    Some(UTF_16LE)
+                } else if data.len() >= 4 && data[0] == 0 && data[2] == 0 && data[1] != 0 {
+                    Some(UTF_16BE)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// UTF-16 输入整体转码为 UTF-8（去除 BOM，非法码元替换为 U+FFFD）；其他编码返回 `None`
+    pub(crate) fn transcode_utf16(self, data: &[u8]) -> Option<Vec<u8>> {
+        let codec = self.utf16(data)?;
+        let body = if data.starts_with(&[0xFF, 0xFE]) || data.starts_with(&[0xFE, 0xFF]) {
+            &data[2..]
+        } else {
+            data
+        };
+        let decoded = codec
+            .decode(body, DecoderTrap::Replace)
+            .unwrap_or_else(|_| String::from_utf8_lossy(body).into_owned());
+        Some(decoded.into_bytes())
+    }
+
+    /// 根据选项与文件内容确定文件级编码 hint
+    ///
+    /// `candidates` 为自动模式下非 UTF-8 文件的候选遗留编码，为空时等价于 `[Gb18030]`。
+    pub(crate) fn resolve(self, data: &[u8], candidates: &[Encoding]) -> FileEncodingHint {
+        match self {
+            Encoding::AutoSampled => {
+                let head_size = data.len().min(64 * 1024);
+                let tail_start = data.len().saturating_sub(4 * 1024).max(head_size);
+                let head = &data[..head_size];
+                let tail = &data[tail_start..];
+                if sampled_is_utf8(head, tail) {
+                    // 采样区外可能仍有非法字节，不能当作已整体校验的 Utf8 使用
+                    if head_size + (data.len() - tail_start) == data.len() {
+                        FileEncodingHint::Utf8
+                    } else {
+                        FileEncodingHint::Utf8Lossy
+                    }
+                } else {
+                    pick_legacy(candidates, &[head, tail])
+                }
+            }
+            Encoding::AutoFullScan => match simd_from_utf8_compat(data) {
+                Ok(_) => FileEncodingHint::Utf8,
+                Err(e) => {
+                    // 以第一个非法字节附近的窗口作为打分样本
+                    let start = e.valid_up_to().saturating_sub(SCORE_SAMPLE / 2);
+                    let end = (start + SCORE_SAMPLE).min(data.len());
+                    pick_legacy(candidates, &[&data[start..end]])
+                }
+            },
+            Encoding::Utf8 => {
+                // FileEncodingHint::Utf8 会跳过逐段校验直接 from_utf8_unchecked，
+                // 因此只有全文件校验通过时才能使用
+                if simd_from_utf8(data).is_ok() {
+                    FileEncodingHint::Utf8
+                } else {
+                    FileEncodingHint::Utf8Lossy
+                }
+            }
+            // UTF-16 已在构建时转码为合法 UTF-8
+            Encoding::Utf16Le | Encoding::Utf16Be => FileEncodingHint::Utf8,
+            Encoding::PerRecord => FileEncodingHint::PerRecord,
+            legacy => legacy.legacy_hint().unwrap_or(FileEncodingHint::Gb18030),
+        }
+    }
+}
+
+/// 无法按所选编码解码的字节的处理方式
+///
+/// 通过 [`LogParserBuilder::decode_policy`](crate::LogParserBuilder::decode_policy) 设置。
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub enum DecodePolicy {
+    /// 有损替换后继续（默认）。
+    ///
+    /// meta 与 tag 在解析时解码，是否有损由 [`Sqllog::is_lossy`](crate::Sqllog::is_lossy) 报告；
+    /// SQL 正文延迟解码，不做额外校验。
+    #[default]
+    Lossy,
+    /// 解析时额外校验 SQL 正文，有损记录通过 `Sqllog::is_lossy` 与
+    /// [`DecodeStats::lossy`] 报告，记录照常产出。
+    Report,
+    /// 记录中存在无法解码的字节时返回 [`ParseError::Encoding`](crate::ParseError::Encoding)。
+    Strict,
+}
+
+/// 解码统计：各编码方式解码的记录数
+///
+/// 由 [`LogIterator::decode_stats`](crate::LogIterator::decode_stats) 返回。
+/// 编码计数仅统计成功解析的记录；`lossy` 同时包含 [`DecodePolicy::Strict`] 下
+/// 因解码失败而返回错误的记录。
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub struct DecodeStats {
+    /// 按 UTF-8 解码的记录数
+    pub utf8: usize,
+    /// 按 GB18030 解码的记录数
+    pub gb18030: usize,
+    /// 按 GBK / GB2312 解码的记录数
+    pub gbk: usize,
+    /// 按 Big5 解码的记录数
+    pub big5: usize,
+    /// 存在无法解码字节（有损替换或解码错误）的记录数
+    pub lossy: usize,
+}
+
+impl DecodeStats {
+    #[inline]
+    pub(crate) fn record(&mut self, encoding: FileEncodingHint) {
+        match encoding {
+            FileEncodingHint::Utf8 | FileEncodingHint::Utf8Lossy => self.utf8 += 1,
+            FileEncodingHint::Gb18030 => self.gb18030 += 1,
+            FileEncodingHint::Gbk => self.gbk += 1,
+            FileEncodingHint::Big5 => self.big5 += 1,
+            FileEncodingHint::Auto | FileEncodingHint::PerRecord => {}
+        }
+    }
+}
+
+/// 遗留编码 hint 对应的解码器
+fn codec(hint: FileEncodingHint) -> EncodingRef {
+    match hint {
+        FileEncodingHint::Gbk => GBK,
+        FileEncodingHint::Big5 => BIG5_2003,
+        _ => GB18030,
+    }
+}
+
+/// hint 对应的编码名称，用于错误信息
+pub(crate) fn encoding_name(hint: FileEncodingHint) -> &'static str {
+    match hint {
+        FileEncodingHint::Gb18030 => "GB18030",
+        FileEncodingHint::Gbk => "GBK",
+        FileEncodingHint::Big5 => "Big5",
+        FileEncodingHint::Utf8
+        | FileEncodingHint::Utf8Lossy
+        | FileEncodingHint::Auto
+        | FileEncodingHint::PerRecord => "UTF-8",
+    }
+}
+
+/// 按遗留编码严格解码，存在非法字节时返回 `None`
+pub(crate) fn try_decode_legacy(hint: FileEncodingHint, bytes: &[u8]) -> Option<String> {
+    codec(hint).decode(bytes, DecoderTrap::Strict).ok()
+}
+
+/// 按遗留编码严格解码，失败时回退为 `from_utf8_lossy`
+pub(crate) fn decode_legacy(hint: FileEncodingHint, bytes: &[u8]) -> String {
+    try_decode_legacy(hint, bytes).unwrap_or_else(|| String::from_utf8_lossy(bytes).into_owned())
+}
+
+/// `bytes` 按 `hint` 解码时第一个无法解码字节的偏移；可完整解码时返回 `None`
+///
+/// `Auto` / `PerRecord` / `Utf8Lossy` 按 UTF-8 校验。`Utf8` 的数据已在构建时校验，直接返回 `None`。
+pub(crate) fn invalid_offset(hint: FileEncodingHint, bytes: &[u8]) -> Option<usize> {
+    match hint {
+        FileEncodingHint::Utf8 => None,
+        FileEncodingHint::Auto | FileEncodingHint::PerRecord | FileEncodingHint::Utf8Lossy => {
+            simd_from_utf8(bytes).err()?;
+            simd_from_utf8_compat(bytes).err().map(|e| e.valid_up_to())
+        }
+        FileEncodingHint::Gb18030 | FileEncodingHint::Gbk | FileEncodingHint::Big5 => {
+            let mut decoder = codec(hint).raw_decoder();
+            let mut out = String::with_capacity(bytes.len());
+            let (processed, err) = decoder.raw_feed(bytes, &mut out);
+            if err.is_some() {
+                return Some(processed);
+            }
+            // 末尾不完整的多字节序列
+            decoder.raw_finish(&mut out).map(|_| processed)
+        }
+    }
+}
+
+/// Detect encoding by sampling the first 64 KB and the last 4 KB.
+///
+/// Sampling both ends catches the rare case where GB18030 content only
+/// appears after the initial UTF-8 section (e.g. late-joined non-ASCII
+/// usernames), while keeping the cost well below a full-file scan.
+///
+/// Known limitation: the middle of large files (> ~68 KB) is not sampled.
+/// GB18030 multi-byte sequences that appear only in the middle of a very
+/// large log file may cause the file to be misclassified as UTF-8, leading
+/// to garbled output for those records. In practice DM log files either use
+/// GB18030 throughout or are entirely ASCII-safe UTF-8, so this edge case
+/// is unlikely. Callers that cannot accept this trade-off should select
+/// [`Encoding::AutoFullScan`] or force the encoding explicitly.
+fn sampled_is_utf8(head: &[u8], tail: &[u8]) -> bool {
+    simd_from_utf8(head).is_ok() && (tail.is_empty() || simd_from_utf8(tail).is_ok())
+}
+
+/// 在候选遗留编码中选出最可能的一个。
+///
+/// 启发式打分（越低越好）：解码出的替换字符计重罚；落在常用汉字区、CJK 标点与
+/// 全角字符之外的非 ASCII 字符计轻罚（错误编码通常解出假名、韩文、私用区等）；
+/// 命中高频汉字（简繁各一组）则加分。分数相同时取候选列表中靠前者。
+fn pick_legacy(candidates: &[Encoding], samples: &[&[u8]]) -> FileEncodingHint {
+    let hints: Vec<FileEncodingHint> = candidates.iter().filter_map(|e| e.legacy_hint()).collect();
+    match hints.as_slice() {
+        [] => FileEncodingHint::Gb18030,
+        [only] => *only,
+        _ => {
+            let mut best = (i64::MAX, FileEncodingHint::Gb18030);
+            for &hint in &hints {
+                let score: i64 = samples.iter().map(|s| score(hint, s)).sum();
+                if score < best.0 {
+                    best = (score, hint);
+                }
+            }
+            best.1
+        }
+    }
+}
+
+/// 简体与繁体各自的高频字
+const FREQUENT_HAN: &str = "的一是不了在人有我他中大上个们来到时和地为国说这年会对用户数据表名称资料戶個們來時為國說這會對資料稱";
+
+fn score(hint: FileEncodingHint, sample: &[u8]) -> i64 {
+    let decoded = codec(hint)
+        .decode(sample, DecoderTrap::Replace)
+        .unwrap_or_default();
+    let mut score = 0i64;
+    for c in decoded.chars().filter(|c| !c.is_ascii()) {
+        score += match c {
+            '\u{FFFD}' => 100,
+            c if FREQUENT_HAN.contains(c) => -3,
+            '\u{4E00}'..='\u{9FFF}' | '\u{3000}'..='\u{303F}' | '\u{FF00}'..='\u{FFEF}' => 0,
+            _ => 5,
+        };
+    }
+    score
+}
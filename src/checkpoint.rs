@@ -0,0 +1,312 @@
+//! 断点续传检查点
+//!
+//! 记录某个文件已处理到的字节偏移，使被重启的任务能从中断位置继续，而不必从头解析。
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::error::ParseError;
+use crate::parser::LogParser;
+
+/// 文件身份标识，用于判断检查点对应的文件是否已被替换
+///
+/// Unix 上为 `(dev, ino)`；其他平台无法稳定获取，两者均为 0（不做身份比较）。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FileIdentity {
+    /// 设备号
+    pub dev: u64,
+    /// inode 号
+    pub ino: u64,
+}
+
+impl FileIdentity {
+    /// 读取文件当前的身份标识
+    pub fn of<P: AsRef<Path>>(path: P) -> Result<Self, ParseError> {
+        let meta = fs::metadata(path.as_ref()).map_err(|e| ParseError::IoError(e.to_string()))?;
+        Ok(Self::from_metadata(&meta))
+    }
+
+    pub(crate) fn from_metadata(meta: &fs::Metadata) -> Self {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            Self {
+                dev: meta.dev(),
+                ino: meta.ino(),
+            }
+        }
+        #[cfg(not(unix))]
+        {
+            let _ = meta;
+            Self::default()
+        }
+    }
+}
+
+/// 可持久化的解析进度
+///
+/// 文本格式为每行一个 `key=value`（值中的 `\`、换行与回车转义为 `\\`、`\n`、`\r`），
+/// 不是合法 UTF-8 的路径以原始编码的十六进制写入 `path_hex`，
+/// 可通过 `to_string()` / `str::parse()` 或 [`Checkpoint::save`] / [`Checkpoint::load`] 读写。
+///
+/// ```rust,no_run
+/// use dm_database_parser_sqllog::{Checkpoint, LogParser};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let cp = Checkpoint::load("job.ckpt")?;
+/// let parser = LogParser::from_path(&cp.path)?;
+/// let mut it = parser.iter_from_checkpoint(&cp)?;
+/// let mut last_ts = cp.last_ts.clone();
+/// for record in it.by_ref().take(10_000).flatten() {
+///     last_ts = Some(record.ts.to_string());
+/// }
+/// Checkpoint::new(&parser, it.position(), last_ts)?.save("job.ckpt")?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Checkpoint {
+    /// 日志文件路径
+    pub path: PathBuf,
+    /// 创建检查点时的文件身份
+    pub identity: FileIdentity,
+    /// 下一条待处理记录的字节偏移（即 [`LogIterator::position`](crate::LogIterator::position)）
+    pub offset: usize,
+    /// 创建检查点时解析器数据（已解压、已转码）的长度，即 [`LogParser::as_bytes`] 的长度
+    pub data_len: usize,
+    /// 最后处理的记录时间戳
+    pub last_ts: Option<String>,
+}
+
+impl Checkpoint {
+    /// 为 `parser` 打开的文件创建检查点（读取文件当前身份与解析器数据长度）。
+    ///
+    /// `parser` 必须由 [`LogParser::from_path`] 或
+    /// [`LogParserBuilder::open`](crate::LogParserBuilder::open) 打开，否则返回
+    /// [`ParseError::NoSourceFile`]。
+    pub fn new(
+        parser: &LogParser,
+        offset: usize,
+        last_ts: Option<String>,
+    ) -> Result<Self, ParseError> {
+        let path = parser.path().ok_or(ParseError::NoSourceFile)?.to_path_buf();
+        let identity = FileIdentity::of(&path)?;
+        Ok(Self {
+            path,
+            identity,
+            offset,
+            data_len: parser.as_bytes().len(),
+            last_ts,
+        })
+    }
+
+    /// 校验检查点可用于 `parser`：
+    ///
+    /// - `parser` 打开的文件就是检查点记录的文件；
+    /// - 文件身份未变（未被替换）；
+    /// - 解析器数据长度不小于创建检查点时的长度（未被截断）。压缩归档比较的是
+    ///   解压后的长度，因此与磁盘上的文件大小无关。
+    pub fn validate(&self, parser: &LogParser) -> Result<(), ParseError> {
+        let mismatch = |reason: &str| ParseError::CheckpointMismatch {
+            path: self.path.display().to_string(),
+            reason: reason.to_string(),
+        };
+        if !parser.path().is_some_and(|p| same_path(p, &self.path)) {
+            return Err(mismatch("parser was not opened from the checkpoint file"));
+        }
+        if FileIdentity::of(&self.path)? != self.identity {
+            return Err(mismatch("file identity changed"));
+        }
+        if parser.as_bytes().len() < self.data_len.max(self.offset) {
+            return Err(mismatch(
+                "data is shorter than when the checkpoint was taken",
+            ));
+        }
+        Ok(())
+    }
+
+    /// 写入检查点文件（先写临时文件再重命名，避免中途崩溃留下半截内容）
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), ParseError> {
+        let path = path.as_ref();
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        fs::write(&tmp, self.to_string()).map_err(|e| ParseError::IoError(e.to_string()))?;
+        fs::rename(&tmp, path).map_err(|e| ParseError::IoError(e.to_string()))
+    }
+
+    /// 读取检查点文件
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ParseError> {
+        fs::read_to_string(path)
+            .map_err(|e| ParseError::IoError(e.to_string()))?
+            .parse()
+    }
+}
+
+impl fmt::Display for Checkpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.path.to_str() {
+            Some(path) => writeln!(f, "path={}", escape(path))?,
+            None => writeln!(f, "path_hex={}", to_hex(&path_to_raw(&self.path)))?,
+        }
+        writeln!(f, "dev={}", self.identity.dev)?;
+        writeln!(f, "ino={}", self.identity.ino)?;
+        writeln!(f, "offset={}", self.offset)?;
+        writeln!(f, "data_len={}", self.data_len)?;
+        if let Some(ts) = &self.last_ts {
+            writeln!(f, "last_ts={}", escape(ts))?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Checkpoint {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |raw: &str| ParseError::InvalidFormat {
+            raw: raw.to_string(),
+        };
+        let number = |key: &str, value: &str| {
+            value.parse::<u64>().map_err(|_| ParseError::IntParseError {
+                field: key.to_string(),
+                value: value.to_string(),
+                raw: s.to_string(),
+            })
+        };
+
+        let mut path: Option<PathBuf> = None;
+        let mut identity = FileIdentity::default();
+        let mut offset: Option<usize> = None;
+        let mut data_len: Option<usize> = None;
+        let mut last_ts: Option<String> = None;
+        for line in s.lines().filter(|l| !l.trim().is_empty()) {
+            let (key, value) = line.split_once('=').ok_or_else(|| invalid(line))?;
+            let text = || unescape(value).ok_or_else(|| invalid(line));
+            match key {
+                "path" => path = Some(PathBuf::from(text()?)),
+                "path_hex" => {
+                    let raw = from_hex(value).ok_or_else(|| invalid(line))?;
+                    path = Some(path_from_raw(raw).ok_or_else(|| invalid(line))?);
+                }
+                "dev" => identity.dev = number(key, value)?,
+                "ino" => identity.ino = number(key, value)?,
+                "offset" => offset = Some(number(key, value)? as usize),
+                "data_len" => data_len = Some(number(key, value)? as usize),
+                "last_ts" => last_ts = Some(text()?),
+                _ => return Err(invalid(line)),
+            }
+        }
+
+        Ok(Self {
+            path: path.ok_or_else(|| invalid(s))?,
+            identity,
+            offset: offset.ok_or_else(|| invalid(s))?,
+            data_len: data_len.ok_or_else(|| invalid(s))?,
+            last_ts,
+        })
+    }
+}
+
+/// 两个路径是否指向同一文件：能规范化时比较规范化结果，否则按字面比较
+fn same_path(a: &Path, b: &Path) -> bool {
+    match (fs::canonicalize(a), fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+/// 转义值中的 `\\`、`\n`、`\r`，使每个 `key=value` 保持单行
+fn escape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// [`escape`] 的逆操作；遇到未知或不完整的转义序列时返回 `None`
+fn unescape(value: &str) -> Option<String> {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next()? {
+            '\\' => out.push('\\'),
+            'n' => out.push('\n'),
+            'r' => out.push('\r'),
+            _ => return None,
+        }
+    }
+    Some(out)
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// [`to_hex`] 的逆操作；长度为奇数或含非十六进制字符时返回 `None`
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// 路径的原始编码：Unix 上为 OS 字节，Windows 上为 UTF-16 码元（小端）
+#[cfg(unix)]
+fn path_to_raw(path: &Path) -> Vec<u8> {
+    use std::os::unix::ffi::OsStrExt;
+    path.as_os_str().as_bytes().to_vec()
+}
+
+#[cfg(unix)]
+fn path_from_raw(raw: Vec<u8>) -> Option<PathBuf> {
+    use std::os::unix::ffi::OsStringExt;
+    Some(std::ffi::OsString::from_vec(raw).into())
+}
+
+#[cfg(windows)]
+fn path_to_raw(path: &Path) -> Vec<u8> {
+    use std::os::windows::ffi::OsStrExt;
+    path.as_os_str()
+        .encode_wide()
+        .flat_map(u16::to_le_bytes)
+        .collect()
+}
+
+#[cfg(windows)]
+fn path_from_raw(raw: Vec<u8>) -> Option<PathBuf> {
+    use std::os::windows::ffi::OsStringExt;
+    if !raw.len().is_multiple_of(2) {
+        return None;
+    }
+    let wide: Vec<u16> = raw
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .collect();
+    Some(std::ffi::OsString::from_wide(&wide).into())
+}
+
+/// 其他平台的路径总是 UTF-8，不会写出 `path_hex`
+#[cfg(not(any(unix, windows)))]
+fn path_to_raw(path: &Path) -> Vec<u8> {
+    path.to_string_lossy().into_owned().into_bytes()
+}
+
+#[cfg(not(any(unix, windows)))]
+fn path_from_raw(raw: Vec<u8>) -> Option<PathBuf> {
+    String::from_utf8(raw).ok().map(PathBuf::from)
+}
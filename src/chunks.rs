@@ -0,0 +1,353 @@
+//! 保序并行解析
+//!
+//! `par_iter()` 的产出顺序不确定；这里把数据按记录边界切成固定大小的块，
+//! 每次并行解析一个窗口内的若干块，再按块序号依次产出。
+
+use rayon::prelude::*;
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::ops::Range;
+use std::sync::{Arc, Mutex, mpsc};
+
+use crate::error::ParseError;
+use crate::parser::{
+    Backing, FileEncodingHint, LogIterator, ParseOptions, find_next_record_start, span_iter,
+};
+use crate::sqllog::Sqllog;
+
+/// 默认块大小
+const DEFAULT_CHUNK_BYTES: usize = 4 * 1024 * 1024;
+
+/// 一块连续记录的解析结果
+#[derive(Debug, Clone)]
+pub struct RecordChunk<'a> {
+    /// 块序号（从 0 开始，按文件顺序递增）
+    pub index: usize,
+    /// 块的起始字节偏移
+    pub offset: usize,
+    /// 块内记录，按文件顺序排列
+    pub records: Vec<Result<Sqllog<'a>, ParseError>>,
+}
+
+/// [`LogParser::par_chunks`](crate::LogParser::par_chunks) 返回的迭代器
+///
+/// 窗口内的块由 Rayon 并行解析后按序号放入缓冲区。调用方消费当前窗口时，下一个窗口
+/// 已在后台解析，因此窗口交替处不会停顿；内存占用约为 `2 × window × chunk_bytes`
+/// 对应的记录量。
+///
+/// ```rust,no_run
+/// use dm_database_parser_sqllog::LogParser;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let parser = LogParser::from_path("sqllog.txt")?;
+/// for chunk in parser.par_chunks().chunk_bytes(1 << 20) {
+///     for record in chunk.records.into_iter().flatten() {
+///         println!("{} {}", record.ts, record.body());
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct ParChunks<'a> {
+    data: &'a [u8],
+    /// 与 `data` 同一份数据，供后台任务持有
+    shared: Arc<Backing>,
+    options: &'a ParseOptions,
+    /// 下一块的起始偏移
+    pos: usize,
+    next_index: usize,
+    chunk_bytes: usize,
+    window: usize,
+    ready: VecDeque<RecordChunk<'a>>,
+    /// 正在后台解析的下一窗口
+    pending: Option<PendingWindow>,
+}
+
+/// 一个窗口内各块的 `(块序号, 起始偏移, 结束偏移)`
+type WindowBounds = Vec<(usize, usize, usize)>;
+
+/// 已提交给 Rayon 的窗口：块边界在任务开始前可由迭代器取回自行解析，
+/// 避免在 Rayon 工作线程上阻塞等待一个尚未被调度的任务
+struct PendingWindow {
+    bounds: Arc<Mutex<Option<WindowBounds>>>,
+    result: mpsc::Receiver<Vec<DetachedChunk>>,
+}
+
+/// 后台任务的解析结果：借用的字段记为相对数据起点的字节范围，
+/// 由迭代器在 [`DetachedChunk::attach`] 中重新借用自己的 `data`
+struct DetachedChunk {
+    index: usize,
+    offset: usize,
+    records: Vec<Result<DetachedRecord, ParseError>>,
+}
+
+/// 借用数据时的字节范围，或解码产生的自有数据
+enum Part<T> {
+    Span(Range<usize>),
+    Owned(T),
+}
+
+struct DetachedRecord {
+    ts: Part<String>,
+    meta_raw: Part<String>,
+    content_raw: Part<Vec<u8>>,
+    tag: Option<Part<String>>,
+    encoding: FileEncodingHint,
+    lossy: bool,
+    utc_offset: i32,
+}
+
+/// `bytes` 在 `data` 中的范围；不在其中时返回 `None`
+fn span_in(data: &[u8], bytes: &[u8]) -> Option<Range<usize>> {
+    let start = (bytes.as_ptr() as usize).checked_sub(data.as_ptr() as usize)?;
+    let end = start.checked_add(bytes.len())?;
+    (end <= data.len()).then_some(start..end)
+}
+
+fn detach_str(data: &[u8], field: Cow<'_, str>) -> Part<String> {
+    match field {
+        Cow::Borrowed(s) => match span_in(data, s.as_bytes()) {
+            Some(range) => Part::Span(range),
+            None => Part::Owned(s.to_owned()),
+        },
+        Cow::Owned(s) => Part::Owned(s),
+    }
+}
+
+fn attach_str(data: &[u8], part: Part<String>) -> Cow<'_, str> {
+    match part {
+        // SAFETY: 范围来自后台任务对同一份数据借出的 `&str`，字节未变，仍是合法 UTF-8
+        Part::Span(range) => Cow::Borrowed(unsafe { std::str::from_utf8_unchecked(&data[range]) }),
+        Part::Owned(s) => Cow::Owned(s),
+    }
+}
+
+impl DetachedRecord {
+    fn new(data: &[u8], record: Sqllog<'_>) -> Self {
+        let content_raw = match record.content_raw {
+            Cow::Borrowed(b) => match span_in(data, b) {
+                Some(range) => Part::Span(range),
+                None => Part::Owned(b.to_vec()),
+            },
+            Cow::Owned(b) => Part::Owned(b),
+        };
+        Self {
+            ts: detach_str(data, record.ts),
+            meta_raw: detach_str(data, record.meta_raw),
+            content_raw,
+            tag: record.tag.map(|tag| detach_str(data, tag)),
+            encoding: record.encoding,
+            lossy: record.lossy,
+            utc_offset: record.utc_offset,
+        }
+    }
+
+    fn attach(self, data: &[u8]) -> Sqllog<'_> {
+        Sqllog {
+            ts: attach_str(data, self.ts),
+            meta_raw: attach_str(data, self.meta_raw),
+            content_raw: match self.content_raw {
+                Part::Span(range) => Cow::Borrowed(&data[range]),
+                Part::Owned(b) => Cow::Owned(b),
+            },
+            tag: self.tag.map(|tag| attach_str(data, tag)),
+            encoding: self.encoding,
+            lossy: self.lossy,
+            utc_offset: self.utc_offset,
+        }
+    }
+}
+
+impl DetachedChunk {
+    fn attach(self, data: &[u8]) -> RecordChunk<'_> {
+        RecordChunk {
+            index: self.index,
+            offset: self.offset,
+            records: self
+                .records
+                .into_iter()
+                .map(|r| r.map(|record| record.attach(data)))
+                .collect(),
+        }
+    }
+}
+
+impl<'a> ParChunks<'a> {
+    pub(crate) fn new(data: &'a Arc<Backing>, options: &'a ParseOptions) -> Self {
+        Self {
+            data,
+            shared: Arc::clone(data),
+            options,
+            pos: 0,
+            next_index: 0,
+            chunk_bytes: DEFAULT_CHUNK_BYTES,
+            window: rayon::current_num_threads().max(1) * 2,
+            ready: VecDeque::new(),
+            pending: None,
+        }
+    }
+
+    /// 设置每块的目标字节数（默认 4 MB）；块总是在记录边界处结束
+    pub fn chunk_bytes(mut self, bytes: usize) -> Self {
+        self.chunk_bytes = bytes.max(1);
+        self
+    }
+
+    /// 设置每轮并行解析、缓冲的最大块数（默认 Rayon 线程数的 2 倍）
+    pub fn window(mut self, chunks: usize) -> Self {
+        self.window = chunks.max(1);
+        self
+    }
+
+    /// 顺序确定接下来 `window` 个块的 `(块序号, 起始偏移, 结束偏移)`；数据耗尽时返回空 `Vec`
+    fn next_bounds(&mut self) -> WindowBounds {
+        let data = self.data;
+        let mut bounds = Vec::with_capacity(self.window);
+        while bounds.len() < self.window && self.pos < data.len() {
+            let start = self.pos;
+            let end = if data.len() - start <= self.chunk_bytes {
+                data.len()
+            } else {
+                find_next_record_start(data, start + self.chunk_bytes - 1)
+            };
+            bounds.push((self.next_index, start, end));
+            self.next_index += 1;
+            self.pos = end;
+        }
+        bounds
+    }
+
+    /// 确定接下来 `window` 个块的边界，并行地对每块调用 `f(块序号, 起始偏移, 块内记录)`，
+    /// 按块序号返回结果；数据耗尽时返回空 `Vec`
+    pub(crate) fn map_window<T, F>(&mut self, f: F) -> Vec<T>
+    where
+        T: Send,
+        F: Fn(usize, usize, LogIterator<'a>) -> T + Sync,
+    {
+        let bounds = self.next_bounds();
+        map_bounds(self.data, self.options, bounds, f)
+    }
+
+    /// 在后台解析下一窗口；数据已耗尽时不提交任务
+    fn prefetch(&mut self) {
+        let bounds = self.next_bounds();
+        if bounds.is_empty() {
+            return;
+        }
+        let bounds = Arc::new(Mutex::new(Some(bounds)));
+        let (tx, rx) = mpsc::sync_channel(1);
+        let shared = Arc::clone(&self.shared);
+        let options = self.options.clone();
+        let task_bounds = Arc::clone(&bounds);
+        rayon::spawn(move || {
+            let Some(bounds) = task_bounds.lock().unwrap_or_else(|e| e.into_inner()).take() else {
+                return;
+            };
+            let data: &[u8] = &shared;
+            let chunks = map_bounds(data, &options, bounds, |index, offset, records| {
+                DetachedChunk {
+                    index,
+                    offset,
+                    records: records
+                        .map(|r| r.map(|record| DetachedRecord::new(data, record)))
+                        .collect(),
+                }
+            });
+            // 迭代器已被丢弃时接收端关闭，结果直接丢弃
+            let _ = tx.send(chunks);
+        });
+        self.pending = Some(PendingWindow { bounds, result: rx });
+    }
+
+    /// 取得后台窗口的结果：任务尚未开始时在当前线程解析；
+    /// 已开始时等待其完成，在 Rayon 工作线程上等待期间先帮忙执行其他任务
+    fn take_pending(&mut self) -> Vec<RecordChunk<'a>> {
+        let Some(pending) = self.pending.take() else {
+            return Vec::new();
+        };
+        let unstarted = pending
+            .bounds
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take();
+        match unstarted {
+            Some(bounds) => parse_chunks(self.data, self.options, bounds),
+            None => {
+                let data = self.data;
+                wait(&pending.result)
+                    .into_iter()
+                    .map(|chunk| chunk.attach(data))
+                    .collect()
+            }
+        }
+    }
+
+    /// 取下一窗口放入缓冲区，并提交再下一个窗口的后台解析
+    fn fill(&mut self) {
+        let chunks = match self.pending {
+            Some(_) => self.take_pending(),
+            None => {
+                let bounds = self.next_bounds();
+                parse_chunks(self.data, self.options, bounds)
+            }
+        };
+        self.ready.extend(chunks);
+        self.prefetch();
+    }
+}
+
+fn wait(result: &mpsc::Receiver<Vec<DetachedChunk>>) -> Vec<DetachedChunk> {
+    loop {
+        match result.try_recv() {
+            Ok(chunks) => return chunks,
+            Err(mpsc::TryRecvError::Disconnected) => return Vec::new(),
+            Err(mpsc::TryRecvError::Empty) => {}
+        }
+        if rayon::yield_now() != Some(rayon::Yield::Executed) {
+            return result.recv().unwrap_or_default();
+        }
+    }
+}
+
+fn map_bounds<'a, T, F>(
+    data: &'a [u8],
+    options: &'a ParseOptions,
+    bounds: WindowBounds,
+    f: F,
+) -> Vec<T>
+where
+    T: Send,
+    F: Fn(usize, usize, LogIterator<'a>) -> T + Sync,
+{
+    let mut out = Vec::with_capacity(bounds.len());
+    bounds
+        .into_par_iter()
+        .map(|(index, start, end)| f(index, start, span_iter(data, start, end, options)))
+        .collect_into_vec(&mut out);
+    out
+}
+
+fn parse_chunks<'a>(
+    data: &'a [u8],
+    options: &'a ParseOptions,
+    bounds: WindowBounds,
+) -> Vec<RecordChunk<'a>> {
+    map_bounds(data, options, bounds, |index, offset, records| {
+        RecordChunk {
+            index,
+            offset,
+            records: records.collect(),
+        }
+    })
+}
+
+impl<'a> Iterator for ParChunks<'a> {
+    type Item = RecordChunk<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.ready.is_empty() {
+            self.fill();
+        }
+        self.ready.pop_front()
+    }
+}
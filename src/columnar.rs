@@ -0,0 +1,209 @@
+//! 列式批处理
+//!
+//! 聚合分析通常只用到少数几列。这里一次遍历把常用列写入可复用的结构体数组
+//! （struct-of-arrays）缓冲区，避免为每条记录构建 [`MetaParts`](crate::MetaParts)
+//! 与 [`PerformanceMetrics`](crate::PerformanceMetrics)。
+
+use std::ops::Range;
+
+use crate::error::ParseError;
+use crate::parser::LogIterator;
+
+/// 一批记录的列式数据
+///
+/// 各列长度相同（[`len`](Self::len)），第 `i` 个元素属于第 `i` 条成功解析的记录；
+/// 解析失败的记录不占行，错误单独收集在 [`errors`](Self::errors) 中。
+/// 通过 [`Batches::next_batch`] 复用：每批开始前清空但保留容量。
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnBatch {
+    ts_millis: Vec<i64>,
+    username_data: String,
+    username_offsets: Vec<usize>,
+    exectime: Vec<f32>,
+    rowcount: Vec<u32>,
+    exec_id: Vec<i64>,
+    body_ranges: Vec<Range<usize>>,
+    errors: Vec<ParseError>,
+}
+
+impl Default for ColumnBatch {
+    fn default() -> Self {
+        Self {
+            ts_millis: Vec::new(),
+            username_data: String::new(),
+            username_offsets: vec![0],
+            exectime: Vec::new(),
+            rowcount: Vec::new(),
+            exec_id: Vec::new(),
+            body_ranges: Vec::new(),
+            errors: Vec::new(),
+        }
+    }
+}
+
+impl ColumnBatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 行数（成功解析的记录数）
+    pub fn len(&self) -> usize {
+        self.ts_millis.len()
+    }
+
+    /// 是否没有任何行
+    pub fn is_empty(&self) -> bool {
+        self.ts_millis.is_empty()
+    }
+
+    /// 清空所有列，保留已分配的容量
+    pub fn clear(&mut self) {
+        self.ts_millis.clear();
+        self.username_data.clear();
+        self.username_offsets.clear();
+        self.username_offsets.push(0);
+        self.exectime.clear();
+        self.rowcount.clear();
+        self.exec_id.clear();
+        self.body_ranges.clear();
+        self.errors.clear();
+    }
+
+    /// UTC Unix 毫秒数（参见 [`Sqllog::timestamp_millis`](crate::Sqllog::timestamp_millis)），
+    /// 时间戳字段越界时为 `i64::MIN`
+    pub fn ts_millis(&self) -> &[i64] {
+        &self.ts_millis
+    }
+
+    /// 第 `i` 行的用户名；记录中没有 `user` 字段时为空字符串
+    pub fn username(&self, i: usize) -> &str {
+        &self.username_data[self.username_offsets[i]..self.username_offsets[i + 1]]
+    }
+
+    /// 所有用户名首尾相接的字符串，按 [`username_offsets`](Self::username_offsets) 切分
+    pub fn username_data(&self) -> &str {
+        &self.username_data
+    }
+
+    /// 用户名在 `username_data` 中的边界，长度为 `len() + 1`（Arrow 风格）
+    pub fn username_offsets(&self) -> &[usize] {
+        &self.username_offsets
+    }
+
+    /// 执行时间（毫秒），无指标时为 0
+    pub fn exectime(&self) -> &[f32] {
+        &self.exectime
+    }
+
+    /// 影响行数，无指标时为 0
+    pub fn rowcount(&self) -> &[u32] {
+        &self.rowcount
+    }
+
+    /// 执行 ID，无指标时为 0
+    pub fn exec_id(&self) -> &[i64] {
+        &self.exec_id
+    }
+
+    /// SQL 语句体在 [`LogParser::as_bytes`](crate::LogParser::as_bytes) 中的字节范围
+    ///
+    /// 为未解码的原始字节；遗留编码文件需自行解码。
+    pub fn body_ranges(&self) -> &[Range<usize>] {
+        &self.body_ranges
+    }
+
+    /// 本批中解析失败的记录
+    pub fn errors(&self) -> &[ParseError] {
+        &self.errors
+    }
+}
+
+/// [`LogParser::batches`](crate::LogParser::batches) 返回的批迭代器
+///
+/// ```rust,no_run
+/// use dm_database_parser_sqllog::LogParser;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let parser = LogParser::from_path("sqllog.txt")?;
+/// let mut batches = parser.batches(8192);
+/// let mut total_ms = 0.0f64;
+/// while let Some(batch) = batches.next_batch() {
+///     total_ms += batch.exectime().iter().map(|&t| t as f64).sum::<f64>();
+/// }
+/// println!("总执行时间 {total_ms} ms");
+/// # Ok(())
+/// # }
+/// ```
+pub struct Batches<'a> {
+    data: &'a [u8],
+    inner: LogIterator<'a>,
+    batch_size: usize,
+    batch: ColumnBatch,
+}
+
+impl<'a> Batches<'a> {
+    pub(crate) fn new(data: &'a [u8], inner: LogIterator<'a>, batch_size: usize) -> Self {
+        Self {
+            data,
+            inner,
+            batch_size: batch_size.max(1),
+            batch: ColumnBatch::default(),
+        }
+    }
+
+    /// 填充并返回下一批（复用内部缓冲区）；数据耗尽时返回 `None`
+    pub fn next_batch(&mut self) -> Option<&ColumnBatch> {
+        fill(self.data, &mut self.inner, self.batch_size, &mut self.batch).then_some(&self.batch)
+    }
+
+    /// 清空 `batch` 后填入最多 `batch_size` 条记录；没有读到任何记录（含错误）时返回 `false`
+    ///
+    /// 解析失败的记录同样计入 `batch_size`，因此错误再多也不会让一批无限增长。
+    /// 适合调用方自行管理多个缓冲区（如交给其他线程聚合）。
+    pub fn fill(&mut self, batch: &mut ColumnBatch) -> bool {
+        fill(self.data, &mut self.inner, self.batch_size, batch)
+    }
+}
+
+fn fill(
+    data: &[u8],
+    inner: &mut LogIterator<'_>,
+    batch_size: usize,
+    batch: &mut ColumnBatch,
+) -> bool {
+    batch.clear();
+    let mut seen = false;
+    while batch.len() + batch.errors.len() < batch_size {
+        let record = match inner.next() {
+            Some(Ok(record)) => record,
+            Some(Err(e)) => {
+                batch.errors.push(e);
+                seen = true;
+                continue;
+            }
+            None => break,
+        };
+        seen = true;
+
+        let (body, indicators) = record.body_range_and_indicators();
+        let pm = indicators.unwrap_or_default();
+        // LogParser 迭代产出的 content_raw 总是借用自 data（无正文时为记录末尾的空切片）
+        let base = (record.content_raw.as_ptr() as usize)
+            .checked_sub(data.as_ptr() as usize)
+            .filter(|&base| base <= data.len())
+            .expect("content_raw must borrow from the parser's data");
+
+        batch
+            .ts_millis
+            .push(record.timestamp_millis().unwrap_or(i64::MIN));
+        batch
+            .username_data
+            .push_str(record.meta_value(b"user").unwrap_or(""));
+        batch.username_offsets.push(batch.username_data.len());
+        batch.exectime.push(pm.exectime);
+        batch.rowcount.push(pm.rowcount);
+        batch.exec_id.push(pm.exec_id);
+        batch.body_ranges.push(base + body.start..base + body.end);
+    }
+    seen
+}
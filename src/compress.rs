@@ -0,0 +1,74 @@
+//! 压缩归档的透明解压
+//!
+//! DM 的历史 sqllog 通常以 `.log.gz` / `.log.zst` / `.log.xz` 形式归档。
+//! 按文件头魔数识别压缩格式，各格式的解码器分别由可选 feature 启用
+//! （`gzip`、`zstd`、`xz`），默认构建不引入任何压缩依赖。
+
+use std::io::Read;
+
+use crate::error::ParseError;
+
+/// 通过文件头魔数识别出的压缩格式
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Compression {
+    None,
+    Gzip,
+    Zstd,
+    Xz,
+}
+
+impl Compression {
+    /// 根据文件起始字节判断压缩格式
+    pub(crate) fn detect(head: &[u8]) -> Self {
+        if head.starts_with(&[0x1F, 0x8B]) {
+            Compression::Gzip
+        } else if head.starts_with(&[0x28, 0xB5, 0x2F, 0xFD]) {
+            Compression::Zstd
+        } else if head.starts_with(&[0xFD, b'7', b'z', b'X', b'Z', 0x00]) {
+            Compression::Xz
+        } else {
+            Compression::None
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Compression::None => "none",
+            Compression::Gzip => "gzip",
+            Compression::Zstd => "zstd",
+            Compression::Xz => "xz",
+        }
+    }
+
+    /// 将压缩数据完整解压到内存
+    pub(crate) fn decompress(self, data: &[u8]) -> Result<Vec<u8>, ParseError> {
+        let mut out = Vec::new();
+        self.decoder(data)?
+            .read_to_end(&mut out)
+            .map_err(|e| ParseError::IoError(e.to_string()))?;
+        Ok(out)
+    }
+
+    /// 为 reader 包装对应的流式解码器；`Compression::None` 时原样返回
+    pub(crate) fn decoder<'r, R: Read + Send + 'r>(
+        self,
+        reader: R,
+    ) -> Result<Box<dyn Read + Send + 'r>, ParseError> {
+        match self {
+            Compression::None => Ok(Box::new(reader)),
+            #[cfg(feature = "gzip")]
+            Compression::Gzip => Ok(Box::new(flate2::read::MultiGzDecoder::new(reader))),
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => Ok(Box::new(
+                zstd::stream::read::Decoder::new(reader)
+                    .map_err(|e| ParseError::IoError(e.to_string()))?,
+            )),
+            #[cfg(feature = "xz")]
+            Compression::Xz => Ok(Box::new(xz2::read::XzDecoder::new_multi_decoder(reader))),
+            #[allow(unreachable_patterns)]
+            other => Err(ParseError::UnsupportedCompression {
+                format: other.name().to_string(),
+            }),
+        }
+    }
+}
@@ -12,7 +12,10 @@ use thiserror::Error;
 ///
 /// 包含了 SQL 日志解析过程中可能遇到的所有错误情况。
 /// 所有错误都包含原始输入数据以便于调试。
+///
+/// 后续版本可能新增错误变体，`match` 时需保留通配分支。
 #[derive(Debug, Clone, PartialEq, Error)]
+#[non_exhaustive]
 pub enum ParseError {
     /// 通用的格式错误
     #[error("invalid format | raw: {raw}")]
@@ -49,4 +52,55 @@ pub enum ParseError {
     /// IO 操作错误
     #[error("IO error: {0}")]
     IoError(String),
+
+    /// 指定的字节偏移不是记录边界
+    #[error("offset {offset} is not a record boundary")]
+    InvalidOffset {
+        /// 字节偏移
+        offset: usize,
+    },
+
+    /// 检查点与当前文件不匹配（文件已被替换或截断）
+    #[error("checkpoint does not match file: {path} ({reason})")]
+    CheckpointMismatch {
+        /// 文件路径
+        path: String,
+        /// 不匹配原因
+        reason: String,
+    },
+
+    /// 索引文件与当前日志文件不匹配（文件长度或修改时间已变化）
+    #[error("record index does not match file: {path} ({reason})")]
+    IndexMismatch {
+        /// 日志文件路径
+        path: String,
+        /// 不匹配原因
+        reason: String,
+    },
+
+    /// 输入为压缩归档，但对应的解压 feature 未启用
+    #[error("unsupported compression format: {format} (enable the matching cargo feature)")]
+    UnsupportedCompression {
+        /// 压缩格式名称（gzip / zstd / xz）
+        format: String,
+    },
+
+    /// 记录中存在无法按所选编码解码的字节（仅 `DecodePolicy::Strict`）
+    #[error("failed to decode record as {encoding} at byte offset {offset} | raw: {raw}")]
+    Encoding {
+        /// 使用的编码名称
+        encoding: String,
+        /// 第一个无法解码字节的偏移（迭代器产出时为相对于数据起点的绝对偏移）
+        offset: usize,
+        /// 原始记录首行（有损解码）
+        raw: String,
+    },
+
+    /// 操作需要日志文件路径，但解析器由内存数据构建（如 `LogParser::from_bytes`）
+    #[error("parser was not opened from a file")]
+    NoSourceFile,
+
+    /// 导出到外部格式（Arrow 等）失败
+    #[error("export error: {0}")]
+    ExportError(String),
 }
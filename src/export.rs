@@ -0,0 +1,583 @@
+//! JSON Lines / CSV 导出
+//!
+//! 由调用方选择输出列，多行 SQL 按各自格式转义（JSON 转义为 `\n`，CSV 整字段加引号）。
+//! `par_write` 借助 [`ParChunks`](crate::ParChunks) 并行解析与格式化，仍按文件顺序写出。
+
+use std::borrow::Cow;
+use std::fmt;
+use std::io::Write;
+use std::str::FromStr;
+
+use crate::error::ParseError;
+use crate::parser::LogParser;
+use crate::sqllog::{MetaParts, Sqllog};
+
+/// 内部缓冲超过该字节数时写入底层输出
+const FLUSH_BYTES: usize = 64 * 1024;
+
+/// 导出列
+///
+/// 列名（[`name`](Self::name)）与 Arrow 导出的字段名一致；
+/// 解析列名时另外接受 `user`、`stmt`、`ip` 等 DM 日志中的原始写法。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Column {
+    Ts,
+    Ep,
+    SessId,
+    ThrdId,
+    User,
+    TrxId,
+    Statement,
+    AppName,
+    /// 记录中没有 `ip` 字段时为空（JSON 中为 `null`）
+    ClientIp,
+    /// 无标签时为空（JSON 中为 `null`）
+    Tag,
+    /// SQL 语句体（ORA 记录已去除 `": "` 前缀）
+    Sql,
+    /// 无性能指标时为空（JSON 中为 `null`），下同
+    ExecTime,
+    RowCount,
+    ExecId,
+}
+
+impl Column {
+    /// 全部列，按记录中出现的顺序排列；两个写入器的默认列
+    pub const ALL: [Column; 14] = [
+        Column::Ts,
+        Column::Ep,
+        Column::SessId,
+        Column::ThrdId,
+        Column::User,
+        Column::TrxId,
+        Column::Statement,
+        Column::AppName,
+        Column::ClientIp,
+        Column::Tag,
+        Column::Sql,
+        Column::ExecTime,
+        Column::RowCount,
+        Column::ExecId,
+    ];
+
+    /// 列名，用作 CSV 表头与 JSON 键
+    pub fn name(self) -> &'static str {
+        match self {
+            Column::Ts => "ts",
+            Column::Ep => "ep",
+            Column::SessId => "sess_id",
+            Column::ThrdId => "thrd_id",
+            Column::User => "username",
+            Column::TrxId => "trxid",
+            Column::Statement => "statement",
+            Column::AppName => "appname",
+            Column::ClientIp => "client_ip",
+            Column::Tag => "tag",
+            Column::Sql => "sql",
+            Column::ExecTime => "exectime",
+            Column::RowCount => "rowcount",
+            Column::ExecId => "exec_id",
+        }
+    }
+
+    fn needs_meta(self) -> bool {
+        matches!(
+            self,
+            Column::Ep
+                | Column::SessId
+                | Column::ThrdId
+                | Column::User
+                | Column::TrxId
+                | Column::Statement
+                | Column::AppName
+                | Column::ClientIp
+        )
+    }
+
+    fn needs_body(self) -> bool {
+        matches!(
+            self,
+            Column::Sql | Column::ExecTime | Column::RowCount | Column::ExecId
+        )
+    }
+}
+
+impl fmt::Display for Column {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Column {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let column = match s.trim().to_ascii_lowercase().as_str() {
+            "ts" => Column::Ts,
+            "ep" => Column::Ep,
+            "sess_id" | "sess" => Column::SessId,
+            "thrd_id" | "thrd" => Column::ThrdId,
+            "username" | "user" => Column::User,
+            "trxid" => Column::TrxId,
+            "statement" | "stmt" => Column::Statement,
+            "appname" => Column::AppName,
+            "client_ip" | "ip" => Column::ClientIp,
+            "tag" => Column::Tag,
+            "sql" => Column::Sql,
+            "exectime" => Column::ExecTime,
+            "rowcount" => Column::RowCount,
+            "exec_id" => Column::ExecId,
+            _ => return Err(ParseError::ExportError(format!("unknown column: {s}"))),
+        };
+        Ok(column)
+    }
+}
+
+/// 并行导出（[`CsvWriter::par_write`] 等）的统计结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ExportStats {
+    /// 写出的行数
+    pub rows: usize,
+    /// 解析失败而跳过的记录数
+    pub errors: usize,
+}
+
+enum Value<'r> {
+    Str(&'r str),
+    Int(i64),
+    Float(f32),
+    Null,
+}
+
+/// 按 `columns` 顺序取出记录的各列值；只在需要时解析元数据与正文
+fn for_each_field(columns: &[Column], record: &Sqllog<'_>, mut f: impl FnMut(Column, Value<'_>)) {
+    let meta = if columns.iter().any(|c| c.needs_meta()) {
+        record.parse_meta()
+    } else {
+        MetaParts::default()
+    };
+    let (sql, pm) = if columns.iter().any(|c| c.needs_body()) {
+        record.sql_and_indicators()
+    } else {
+        (Cow::Borrowed(""), None)
+    };
+    for &column in columns {
+        let value = match column {
+            Column::Ts => Value::Str(&record.ts),
+            Column::Ep => Value::Int(meta.ep as i64),
+            Column::SessId => Value::Str(&meta.sess_id),
+            Column::ThrdId => Value::Str(&meta.thrd_id),
+            Column::User => Value::Str(&meta.username),
+            Column::TrxId => Value::Str(&meta.trxid),
+            Column::Statement => Value::Str(&meta.statement),
+            Column::AppName => Value::Str(&meta.appname),
+            Column::ClientIp if meta.client_ip.is_empty() => Value::Null,
+            Column::ClientIp => Value::Str(&meta.client_ip),
+            Column::Tag => record.tag.as_deref().map_or(Value::Null, Value::Str),
+            Column::Sql => Value::Str(&sql),
+            Column::ExecTime => pm
+                .as_ref()
+                .map_or(Value::Null, |pm| Value::Float(pm.exectime)),
+            Column::RowCount => pm
+                .as_ref()
+                .map_or(Value::Null, |pm| Value::Int(pm.rowcount as i64)),
+            Column::ExecId => pm.as_ref().map_or(Value::Null, |pm| Value::Int(pm.exec_id)),
+        };
+        f(column, value);
+    }
+}
+
+fn push_number(buf: &mut Vec<u8>, value: &Value<'_>) {
+    // 写入 Vec 不会失败
+    let _ = match *value {
+        Value::Int(v) => write!(buf, "{v}"),
+        Value::Float(v) => write!(buf, "{v}"),
+        _ => Ok(()),
+    };
+}
+
+fn io_error(e: std::io::Error) -> ParseError {
+    ParseError::IoError(e.to_string())
+}
+
+/// 并行解析、格式化 `parser` 中的记录，按文件顺序写入 `out`
+fn par_write_with<W, F>(out: &mut W, parser: &LogParser, row: F) -> Result<ExportStats, ParseError>
+where
+    W: Write,
+    F: Fn(&Sqllog<'_>, &mut Vec<u8>) + Sync,
+{
+    let mut chunks = parser.par_chunks();
+    let mut stats = ExportStats::default();
+    loop {
+        let parts = chunks.map_window(|_, _, records| {
+            let mut buf = Vec::new();
+            let mut part = ExportStats::default();
+            for record in records {
+                match record {
+                    Ok(record) => {
+                        row(&record, &mut buf);
+                        part.rows += 1;
+                    }
+                    Err(_) => part.errors += 1,
+                }
+            }
+            (buf, part)
+        });
+        if parts.is_empty() {
+            return Ok(stats);
+        }
+        for (buf, part) in parts {
+            out.write_all(&buf).map_err(io_error)?;
+            stats.rows += part.rows;
+            stats.errors += part.errors;
+        }
+    }
+}
+
+/// 两个写入器共用的输出：行先格式化到内部缓冲区，超过 [`FLUSH_BYTES`] 时写入底层输出。
+///
+/// 丢弃时尽力写出缓冲区并刷新底层输出（错误被忽略），与 `BufWriter` 一致；
+/// 需要得知写入错误时应显式调用 `flush()` 或 `into_inner()`。
+struct BufferedOutput<W: Write> {
+    /// 仅在 `into_inner` 取回后为 `None`
+    out: Option<W>,
+    buf: Vec<u8>,
+}
+
+impl<W: Write> BufferedOutput<W> {
+    fn new(out: W) -> Self {
+        Self {
+            out: Some(out),
+            buf: Vec::new(),
+        }
+    }
+
+    fn out(&mut self) -> &mut W {
+        self.out.as_mut().expect("output taken by into_inner")
+    }
+
+    /// 用 `row` 格式化一行到缓冲区，必要时写出
+    fn push_row(&mut self, row: impl FnOnce(&mut Vec<u8>)) -> Result<(), ParseError> {
+        row(&mut self.buf);
+        if self.buf.len() >= FLUSH_BYTES {
+            self.flush_buf()?;
+        }
+        Ok(())
+    }
+
+    fn flush_buf(&mut self) -> Result<(), ParseError> {
+        let Some(out) = self.out.as_mut() else {
+            return Ok(());
+        };
+        out.write_all(&self.buf).map_err(io_error)?;
+        self.buf.clear();
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), ParseError> {
+        self.flush_buf()?;
+        self.out().flush().map_err(io_error)
+    }
+
+    /// 写出缓冲区后并行导出，参见 [`par_write_with`]
+    fn par_write<F>(&mut self, parser: &LogParser, row: F) -> Result<ExportStats, ParseError>
+    where
+        F: Fn(&Sqllog<'_>, &mut Vec<u8>) + Sync,
+    {
+        self.flush_buf()?;
+        par_write_with(self.out(), parser, row)
+    }
+
+    fn into_inner(mut self) -> Result<W, ParseError> {
+        self.flush()?;
+        Ok(self.out.take().expect("output taken by into_inner"))
+    }
+}
+
+impl<W: Write> Drop for BufferedOutput<W> {
+    fn drop(&mut self) {
+        if let Some(out) = self.out.as_mut() {
+            let _ = out.write_all(&self.buf);
+            let _ = out.flush();
+        }
+    }
+}
+
+/// 依次把 `records` 交给 `write`，返回写出的行数
+fn write_each<'a, I>(
+    records: I,
+    mut write: impl FnMut(&Sqllog<'a>) -> Result<(), ParseError>,
+) -> Result<usize, ParseError>
+where
+    I: IntoIterator<Item = Sqllog<'a>>,
+{
+    let mut rows = 0;
+    for record in records {
+        write(&record)?;
+        rows += 1;
+    }
+    Ok(rows)
+}
+
+// ── CSV ───────────────────────────────────────────────────────────────────────
+
+fn push_csv_field(buf: &mut Vec<u8>, s: &str, delimiter: u8) {
+    let needs_quote = s
+        .bytes()
+        .any(|b| b == delimiter || b == b'"' || b == b'\n' || b == b'\r');
+    if !needs_quote {
+        buf.extend_from_slice(s.as_bytes());
+        return;
+    }
+    buf.push(b'"');
+    for b in s.bytes() {
+        if b == b'"' {
+            buf.push(b'"');
+        }
+        buf.push(b);
+    }
+    buf.push(b'"');
+}
+
+fn csv_row(columns: &[Column], delimiter: u8, record: &Sqllog<'_>, buf: &mut Vec<u8>) {
+    let mut first = true;
+    for_each_field(columns, record, |_, value| {
+        if !std::mem::take(&mut first) {
+            buf.push(delimiter);
+        }
+        match value {
+            Value::Str(s) => push_csv_field(buf, s, delimiter),
+            Value::Null => {}
+            number => push_number(buf, &number),
+        }
+    });
+    buf.push(b'\n');
+}
+
+/// CSV 写入器（RFC 4180 引号规则，行尾为 `\n`）
+///
+/// 包含分隔符、双引号或换行的字段整体加双引号，内部双引号加倍，
+/// 因此多行 SQL 可被标准 CSV 读取器正确还原。
+///
+/// ```rust,no_run
+/// use dm_database_parser_sqllog::{Column, CsvWriter, LogParser};
+/// use std::fs::File;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let parser = LogParser::from_path("sqllog.txt")?;
+/// let mut writer = CsvWriter::new(File::create("sqllog.csv")?)
+///     .columns([Column::Ts, Column::User, Column::AppName, Column::ExecTime, Column::Sql]);
+/// let stats = writer.par_write(&parser)?;
+/// writer.into_inner()?;
+/// println!("{} 行，{} 条解析失败", stats.rows, stats.errors);
+/// # Ok(())
+/// # }
+/// ```
+pub struct CsvWriter<W: Write> {
+    out: BufferedOutput<W>,
+    columns: Vec<Column>,
+    delimiter: u8,
+    headers: bool,
+    header_written: bool,
+}
+
+impl<W: Write> CsvWriter<W> {
+    /// 输出全部列（[`Column::ALL`]），逗号分隔，带表头
+    pub fn new(out: W) -> Self {
+        Self {
+            out: BufferedOutput::new(out),
+            columns: Column::ALL.to_vec(),
+            delimiter: b',',
+            headers: true,
+            header_written: false,
+        }
+    }
+
+    /// 选择输出列及顺序
+    pub fn columns<I: IntoIterator<Item = Column>>(mut self, columns: I) -> Self {
+        self.columns = columns.into_iter().collect();
+        self
+    }
+
+    /// 字段分隔符（默认 `,`）
+    ///
+    /// `"`、`\n` 与 `\r` 无法与引号、行尾区分，返回 [`ParseError::ExportError`]。
+    pub fn delimiter(mut self, delimiter: u8) -> Result<Self, ParseError> {
+        if matches!(delimiter, b'"' | b'\n' | b'\r') {
+            return Err(ParseError::ExportError(format!(
+                "CSV delimiter must not be a quote or line break: {:?}",
+                delimiter as char
+            )));
+        }
+        self.delimiter = delimiter;
+        Ok(self)
+    }
+
+    /// 是否输出表头（默认输出）
+    pub fn has_headers(mut self, yes: bool) -> Self {
+        self.headers = yes;
+        self
+    }
+
+    fn write_header(&mut self) {
+        if std::mem::replace(&mut self.header_written, true) || !self.headers {
+            return;
+        }
+        let buf = &mut self.out.buf;
+        for (i, column) in self.columns.iter().enumerate() {
+            if i > 0 {
+                buf.push(self.delimiter);
+            }
+            push_csv_field(buf, column.name(), self.delimiter);
+        }
+        buf.push(b'\n');
+    }
+
+    /// 写入一条记录
+    pub fn write(&mut self, record: &Sqllog<'_>) -> Result<(), ParseError> {
+        self.write_header();
+        let (columns, delimiter) = (&self.columns, self.delimiter);
+        self.out
+            .push_row(|buf| csv_row(columns, delimiter, record, buf))
+    }
+
+    /// 依次写入全部记录，返回写出的行数
+    pub fn write_all<'a, I>(&mut self, records: I) -> Result<usize, ParseError>
+    where
+        I: IntoIterator<Item = Sqllog<'a>>,
+    {
+        write_each(records, |record| self.write(record))
+    }
+
+    /// 并行解析并格式化 `parser` 的全部记录，按文件顺序写出；解析失败的记录被跳过并计数
+    pub fn par_write(&mut self, parser: &LogParser) -> Result<ExportStats, ParseError> {
+        self.write_header();
+        let (columns, delimiter) = (&self.columns, self.delimiter);
+        self.out.par_write(parser, |record, buf| {
+            csv_row(columns, delimiter, record, buf)
+        })
+    }
+
+    /// 写出缓冲数据并刷新底层输出
+    ///
+    /// 写入器被丢弃时也会尽力写出缓冲数据，但会忽略错误。
+    pub fn flush(&mut self) -> Result<(), ParseError> {
+        self.write_header();
+        self.out.flush()
+    }
+
+    /// 刷新后取回底层输出
+    pub fn into_inner(mut self) -> Result<W, ParseError> {
+        self.write_header();
+        self.out.into_inner()
+    }
+}
+
+// ── JSON Lines ────────────────────────────────────────────────────────────────
+
+fn push_json_str(buf: &mut Vec<u8>, s: &str) {
+    const HEX: &[u8; 16] = b"0123456789abcdef";
+    buf.push(b'"');
+    let bytes = s.as_bytes();
+    let mut start = 0;
+    for (i, &b) in bytes.iter().enumerate() {
+        let escape: &[u8] = match b {
+            b'"' => b"\\\"",
+            b'\\' => b"\\\\",
+            b'\n' => b"\\n",
+            b'\r' => b"\\r",
+            b'\t' => b"\\t",
+            0x00..=0x1f => &[
+                b'\\',
+                b'u',
+                b'0',
+                b'0',
+                HEX[(b >> 4) as usize],
+                HEX[(b & 0xf) as usize],
+            ],
+            _ => continue,
+        };
+        buf.extend_from_slice(&bytes[start..i]);
+        buf.extend_from_slice(escape);
+        start = i + 1;
+    }
+    buf.extend_from_slice(&bytes[start..]);
+    buf.push(b'"');
+}
+
+fn json_row(columns: &[Column], record: &Sqllog<'_>, buf: &mut Vec<u8>) {
+    buf.push(b'{');
+    let mut first = true;
+    for_each_field(columns, record, |column, value| {
+        if !std::mem::take(&mut first) {
+            buf.push(b',');
+        }
+        push_json_str(buf, column.name());
+        buf.push(b':');
+        match value {
+            Value::Str(s) => push_json_str(buf, s),
+            Value::Null => buf.extend_from_slice(b"null"),
+            Value::Float(v) if !v.is_finite() => buf.extend_from_slice(b"null"),
+            number => push_number(buf, &number),
+        }
+    });
+    buf.extend_from_slice(b"}\n");
+}
+
+/// JSON Lines 写入器：每条记录一行 JSON 对象，键为 [`Column::name`]
+///
+/// 数值列输出为 JSON 数字，缺失值为 `null`；字符串中的换行等控制字符按 JSON 规则转义，
+/// 因此多行 SQL 仍占一行。
+pub struct JsonLinesWriter<W: Write> {
+    out: BufferedOutput<W>,
+    columns: Vec<Column>,
+}
+
+impl<W: Write> JsonLinesWriter<W> {
+    /// 输出全部列（[`Column::ALL`]）
+    pub fn new(out: W) -> Self {
+        Self {
+            out: BufferedOutput::new(out),
+            columns: Column::ALL.to_vec(),
+        }
+    }
+
+    /// 选择输出列及顺序
+    pub fn columns<I: IntoIterator<Item = Column>>(mut self, columns: I) -> Self {
+        self.columns = columns.into_iter().collect();
+        self
+    }
+
+    /// 写入一条记录
+    pub fn write(&mut self, record: &Sqllog<'_>) -> Result<(), ParseError> {
+        let columns = &self.columns;
+        self.out.push_row(|buf| json_row(columns, record, buf))
+    }
+
+    /// 依次写入全部记录，返回写出的行数
+    pub fn write_all<'a, I>(&mut self, records: I) -> Result<usize, ParseError>
+    where
+        I: IntoIterator<Item = Sqllog<'a>>,
+    {
+        write_each(records, |record| self.write(record))
+    }
+
+    /// 并行解析并格式化 `parser` 的全部记录，按文件顺序写出；解析失败的记录被跳过并计数
+    pub fn par_write(&mut self, parser: &LogParser) -> Result<ExportStats, ParseError> {
+        let columns = &self.columns;
+        self.out
+            .par_write(parser, |record, buf| json_row(columns, record, buf))
+    }
+
+    /// 写出缓冲数据并刷新底层输出
+    ///
+    /// 写入器被丢弃时也会尽力写出缓冲数据，但会忽略错误。
+    pub fn flush(&mut self) -> Result<(), ParseError> {
+        self.out.flush()
+    }
+
+    /// 刷新后取回底层输出
+    pub fn into_inner(self) -> Result<W, ParseError> {
+        self.out.into_inner()
+    }
+}
@@ -0,0 +1,113 @@
+//! SQL 指纹：把只有字面量不同的语句归为同一形态
+
+use crate::lexer::{Lexer, Token, TokenKind};
+use crate::sqllog::Sqllog;
+
+/// 规范化后的语句文本及其 64 位哈希
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Fingerprint {
+    /// 规范化文本，如 `select * from t where id in(...) and name = ?`
+    pub text: String,
+    /// `text` 的 FNV-1a 哈希，跨平台、跨版本稳定，可持久化用于分组
+    pub hash: u64,
+}
+
+/// 计算 SQL 语句的指纹
+///
+/// - 字符串、数字（含负号）与绑定参数替换为 `?`
+/// - 只含字面量的 `IN (...)` 列表折叠为 `in(...)`
+/// - 注释去除，空白折叠；未加引号的关键字与标识符转为小写（DM 中二者均不区分大小写），
+///   带引号的标识符原样保留
+/// - 去除末尾的 `;` 及 DM 在性能指标前追加的 `.`
+///
+/// ```rust
+/// use dm_database_parser_sqllog::fingerprint;
+///
+/// let a = fingerprint("SELECT * FROM t WHERE id = 1 AND name IN ('a', 'b')");
+/// let b = fingerprint("select *\n  from T where ID = 42 and NAME in ('c')");
+/// assert_eq!(a, b);
+/// assert_eq!(a.text, "select * from t where id = ? and name in(...)");
+/// ```
+pub fn fingerprint(sql: &str) -> Fingerprint {
+    let tokens: Vec<Token<'_>> = Lexer::code(sql).collect();
+    let mut text = String::with_capacity(sql.len());
+    let mut prev: Option<Token<'_>> = None;
+    let mut i = 0;
+
+    while i < tokens.len() {
+        let token = tokens[i];
+        i += 1;
+
+        // 一元负号与后面的数字一起视为字面量
+        let unary = token.is_punct("-")
+            && tokens.get(i).is_some_and(|t| t.kind == TokenKind::Number)
+            && prev.is_none_or(|p| p.kind == TokenKind::Punct && p.text != ")");
+        if unary {
+            continue;
+        }
+
+        if token.is_punct("(")
+            && prev.is_some_and(|p| p.is_word("in"))
+            && let Some(len) = literal_list_len(&tokens[i..])
+        {
+            text.push_str("(...)");
+            i += len;
+            prev = Some(tokens[i - 1]);
+            continue;
+        }
+
+        let word;
+        let out = match token.kind {
+            TokenKind::String | TokenKind::Number | TokenKind::Param => "?",
+            TokenKind::Word => {
+                word = token.text.to_ascii_lowercase();
+                &word
+            }
+            _ => token.text,
+        };
+        if let Some(p) = prev {
+            let glued = matches!(p.text, "(" | ".")
+                || matches!(out, ")" | "," | "." | ";")
+                || (out == "(" && p.kind != TokenKind::Punct);
+            if !glued {
+                text.push(' ');
+            }
+        }
+        text.push_str(out);
+        prev = Some(token);
+    }
+
+    while text.ends_with(';') || text.ends_with('.') {
+        text.pop();
+        text.truncate(text.trim_end().len());
+    }
+
+    let hash = fnv1a(text.as_bytes());
+    Fingerprint { text, hash }
+}
+
+/// `tokens` 从 `(` 之后开始；若直到匹配的 `)` 只有字面量、逗号与负号，返回含 `)` 的 token 数
+fn literal_list_len(tokens: &[Token<'_>]) -> Option<usize> {
+    for (n, t) in tokens.iter().enumerate() {
+        if t.is_punct(")") {
+            return (n > 0).then_some(n + 1);
+        }
+        if !(t.is_value() || t.is_punct(",") || t.is_punct("-")) {
+            return None;
+        }
+    }
+    None
+}
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |h, &b| {
+        (h ^ b as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+impl Sqllog<'_> {
+    /// SQL 语句体（不含性能指标，ORA 记录已去除 `": "` 前缀）的指纹，参见 [`fingerprint`]
+    pub fn fingerprint(&self) -> Fingerprint {
+        fingerprint(&self.sql_and_indicators().0)
+    }
+}
@@ -0,0 +1,325 @@
+//! 跟踪模式（类似 `tail -f`）：持续产出 DM 追加写入的新记录。
+//!
+//! 通过轮询文件长度发现新数据，不依赖 inotify 等平台相关机制。
+//! 当 DM 按 `SQL_LOG_FILE_SIZE` / `FILE_NUM` 切换到新文件时，自动切换到同一实例的下一个文件。
+
+use std::fs::{self, File};
+use std::io::{ErrorKind, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::checkpoint::FileIdentity;
+use crate::error::ParseError;
+use crate::parser::{LogParserBuilder, ParseOptions, is_timestamp_start};
+use crate::sqllog::Sqllog;
+use crate::stream::{RecordBuffer, parse_owned};
+
+/// 单次轮询最多读取的字节数，避免追赶大文件时一次性占用过多内存
+const MAX_READ_PER_POLL: u64 = 4 * 1024 * 1024;
+
+/// 解析 DM sqllog 文件名 `dmsql_<inst>_<yyyymmdd>_<hhmmss>.log`。
+///
+/// 返回 `(前缀, yyyymmddhhmmss)`，前缀为 `dmsql_<inst>`，用于识别同一实例的轮转文件；
+/// 文件名不符合该格式时返回 `None`。
+pub(crate) fn parse_dm_file_name(name: &str) -> Option<(&str, u64)> {
+    let stem = name.strip_suffix(".log")?;
+    let mut parts = stem.rsplitn(3, '_');
+    let time = parts.next()?;
+    let date = parts.next()?;
+    let prefix = parts.next()?;
+    let valid = |s: &str, len: usize| s.len() == len && s.bytes().all(|b| b.is_ascii_digit());
+    if !prefix.starts_with("dmsql_") || !valid(date, 8) || !valid(time, 6) {
+        return None;
+    }
+    let stamp = date.parse::<u64>().ok()? * 1_000_000 + time.parse::<u64>().ok()?;
+    Some((prefix, stamp))
+}
+
+/// 跟踪正在写入的 sqllog 文件，持续产出新追加的记录
+///
+/// - 最后一条记录可能尚未写完：只有在出现下一条记录的时间戳行、或超过
+///   `idle_timeout` 没有新数据时才会产出。超时产出后若该记录又追加了续行，
+///   续行会被丢弃（记录已产出，无法再合并），直到下一条时间戳行为止。
+/// - 当目录中出现同一实例的更新文件（按 DM 文件名中的时间排序）且当前文件已读完时，
+///   切换到新文件继续跟踪。目录扫描至多每 `rotation_check_interval` 一次。
+/// - 文件长度变小（被截断）时从头重新读取。
+/// - 路径被指向另一个文件（如 logrotate 先改名再新建同名文件）时，读完原文件后
+///   重新打开该路径从头读取。判定依据为 [`FileIdentity`]，仅 Unix 上可用。
+///
+/// ```rust,no_run
+/// use dm_database_parser_sqllog::LogFollower;
+/// use std::time::Duration;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let follower = LogFollower::open("dmsql_DMSERVER_20250812_092516.log")?
+///     .poll_interval(Duration::from_millis(200));
+/// for result in follower {
+///     match result {
+///         Ok(sqllog) => println!("{} {}", sqllog.ts, sqllog.body()),
+///         Err(e) => eprintln!("解析错误: {}", e),
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct LogFollower {
+    path: PathBuf,
+    file: File,
+    /// `file` 打开时的身份，用于发现路径被替换
+    identity: FileIdentity,
+    offset: u64,
+    buffer: RecordBuffer,
+    poll_interval: Duration,
+    idle_timeout: Duration,
+    last_growth: Instant,
+    rotation_check_interval: Duration,
+    last_rotation_check: Option<Instant>,
+    /// 上一条记录因空闲超时被提前产出，缓冲区开头可能是它的续行
+    flushed_partial: bool,
+    options: ParseOptions,
+}
+
+impl LogFollower {
+    /// 从文件开头开始跟踪
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, ParseError> {
+        Self::open_at(path, 0)
+    }
+
+    /// 从指定字节偏移开始跟踪。
+    ///
+    /// 与 [`LogParser::iter_from`](crate::LogParser::iter_from) 相同，`offset` 必须是记录边界：
+    /// `0`、文件末尾，或紧跟在 `'\n'` 之后的时间戳行起点；否则返回 [`ParseError::InvalidOffset`]。
+    pub fn open_at<P: AsRef<Path>>(path: P, offset: u64) -> Result<Self, ParseError> {
+        let path = path.as_ref().to_path_buf();
+        let (mut file, identity) = open_with_identity(&path)?;
+        if !is_record_boundary(&mut file, offset)? {
+            return Err(ParseError::InvalidOffset {
+                offset: offset as usize,
+            });
+        }
+        Ok(Self {
+            path,
+            file,
+            identity,
+            offset,
+            buffer: RecordBuffer::default(),
+            poll_interval: Duration::from_millis(500),
+            idle_timeout: Duration::from_secs(2),
+            last_growth: Instant::now(),
+            rotation_check_interval: Duration::from_secs(2),
+            last_rotation_check: None,
+            flushed_partial: false,
+            options: LogParserBuilder::new().streaming_options(),
+        })
+    }
+
+    /// 无新数据时两次轮询之间的等待时间（仅影响阻塞式迭代），默认 500 ms
+    pub fn poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// 文件超过该时长没有增长时，视末尾未完成的记录为已写完并产出，默认 2 s
+    pub fn idle_timeout(mut self, timeout: Duration) -> Self {
+        self.idle_timeout = timeout;
+        self
+    }
+
+    /// 使用 `builder` 中的编码、解码策略、时区与标签设置解析记录，
+    /// 限制同 [`StreamingLogParser::options`](crate::StreamingLogParser::options)。
+    pub fn options(mut self, builder: LogParserBuilder) -> Self {
+        self.options = builder.streaming_options();
+        self
+    }
+
+    /// 两次扫描目录查找轮转文件之间的最小间隔，默认 2 s
+    pub fn rotation_check_interval(mut self, interval: Duration) -> Self {
+        self.rotation_check_interval = interval;
+        self
+    }
+
+    /// 当前正在跟踪的文件路径（轮转后会变化）
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 当前文件中已读取的字节偏移
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// 非阻塞地取出下一条可用记录；当前没有可产出的记录时返回 `None`。
+    pub fn poll_next(&mut self) -> Option<Result<Sqllog<'static>, ParseError>> {
+        loop {
+            if self.flushed_partial && self.buffer.skip_to_record_start() {
+                self.flushed_partial = false;
+            }
+            if !self.flushed_partial
+                && let Some(record) = self.buffer.next_record(false)
+            {
+                return Some(parse_owned(record, &self.options));
+            }
+
+            match self.read_new_data() {
+                Ok(true) => continue,
+                Ok(false) => {}
+                Err(e) => return Some(Err(e)),
+            }
+
+            // 当前文件已无新数据：若 DM 已切换到新文件或路径已被替换，先产出旧文件的最后一条记录
+            let next = self.find_successor().or_else(|| self.replaced_path());
+            if let Some(next) = next {
+                if !self.flushed_partial
+                    && let Some(record) = self.buffer.next_record(true)
+                {
+                    return Some(parse_owned(record, &self.options));
+                }
+                if let Err(e) = self.switch_to(next) {
+                    return Some(Err(e));
+                }
+                continue;
+            }
+
+            if !self.flushed_partial
+                && self.buffer.has_pending()
+                && self.last_growth.elapsed() >= self.idle_timeout
+            {
+                self.flushed_partial = true;
+                let options = &self.options;
+                return self
+                    .buffer
+                    .next_record(true)
+                    .map(|record| parse_owned(record, options));
+            }
+            return None;
+        }
+    }
+
+    /// 读取文件新增部分；返回是否读到了新数据
+    fn read_new_data(&mut self) -> Result<bool, ParseError> {
+        let len = self
+            .file
+            .metadata()
+            .map_err(|e| ParseError::IoError(e.to_string()))?
+            .len();
+        if len < self.offset {
+            // 文件被截断或替换：从头重新读取
+            self.offset = 0;
+            self.buffer.clear();
+            self.flushed_partial = false;
+        }
+        if len == self.offset {
+            return Ok(false);
+        }
+
+        let to_read = (len - self.offset).min(MAX_READ_PER_POLL) as usize;
+        let mut chunk = vec![0u8; to_read];
+        self.file
+            .seek(SeekFrom::Start(self.offset))
+            .map_err(|e| ParseError::IoError(e.to_string()))?;
+        let mut filled = 0;
+        while filled < to_read {
+            match self.file.read(&mut chunk[filled..]) {
+                Ok(0) => break,
+                Ok(n) => filled += n,
+                Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => return Err(ParseError::IoError(e.to_string())),
+            }
+        }
+        if filled == 0 {
+            return Ok(false);
+        }
+        self.buffer.extend(&chunk[..filled]);
+        self.offset += filled as u64;
+        self.last_growth = Instant::now();
+        Ok(true)
+    }
+
+    /// 在同一目录中查找同一实例、时间上紧随当前文件的下一个 sqllog 文件。
+    ///
+    /// 距上次扫描不足 `rotation_check_interval` 时直接返回 `None`。
+    fn find_successor(&mut self) -> Option<PathBuf> {
+        if self
+            .last_rotation_check
+            .is_some_and(|at| at.elapsed() < self.rotation_check_interval)
+        {
+            return None;
+        }
+        self.last_rotation_check = Some(Instant::now());
+
+        let name = self.path.file_name()?.to_str()?;
+        let (prefix, stamp) = parse_dm_file_name(name)?;
+        let dir = match self.path.parent() {
+            Some(p) if !p.as_os_str().is_empty() => p,
+            _ => Path::new("."),
+        };
+
+        let mut best: Option<(u64, PathBuf)> = None;
+        for entry in fs::read_dir(dir).ok()?.flatten() {
+            let file_name = entry.file_name();
+            let Some((p, s)) = file_name.to_str().and_then(parse_dm_file_name) else {
+                continue;
+            };
+            if p == prefix && s > stamp && best.as_ref().is_none_or(|(b, _)| s < *b) {
+                best = Some((s, entry.path()));
+            }
+        }
+        best.map(|(_, path)| path)
+    }
+
+    /// 路径当前指向的文件与已打开的不同时返回该路径；路径暂时不存在时视为未替换
+    fn replaced_path(&self) -> Option<PathBuf> {
+        let current = FileIdentity::of(&self.path).ok()?;
+        (current != self.identity).then(|| self.path.clone())
+    }
+
+    fn switch_to(&mut self, path: PathBuf) -> Result<(), ParseError> {
+        (self.file, self.identity) = open_with_identity(&path)?;
+        self.path = path;
+        self.offset = 0;
+        self.buffer.clear();
+        self.flushed_partial = false;
+        self.last_growth = Instant::now();
+        Ok(())
+    }
+}
+
+/// 打开文件并从打开的句柄读取身份，避免打开前后路径被替换造成不一致
+fn open_with_identity(path: &Path) -> Result<(File, FileIdentity), ParseError> {
+    let io_err = |e: std::io::Error| ParseError::IoError(e.to_string());
+    let file = File::open(path).map_err(io_err)?;
+    let identity = FileIdentity::from_metadata(&file.metadata().map_err(io_err)?);
+    Ok((file, identity))
+}
+
+/// `offset` 是否为 `file` 中的记录边界（判定规则同 `LogParser::iter_from`）
+fn is_record_boundary(file: &mut File, offset: u64) -> Result<bool, ParseError> {
+    let io_err = |e: std::io::Error| ParseError::IoError(e.to_string());
+    let len = file.metadata().map_err(io_err)?.len();
+    if offset == 0 || offset == len {
+        return Ok(true);
+    }
+    if offset > len || len - offset < 23 {
+        return Ok(false);
+    }
+    // 前一字节 '\n' + 23 字节时间戳
+    let mut probe = [0u8; 24];
+    file.seek(SeekFrom::Start(offset - 1)).map_err(io_err)?;
+    file.read_exact(&mut probe).map_err(io_err)?;
+    Ok(probe[0] == b'\n' && is_timestamp_start(&probe[1..]))
+}
+
+impl Iterator for LogFollower {
+    type Item = Result<Sqllog<'static>, ParseError>;
+
+    /// 阻塞直到有新记录可用；跟踪模式下迭代永不结束。
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.poll_next() {
+                return Some(item);
+            }
+            thread::sleep(self.poll_interval);
+        }
+    }
+}
@@ -0,0 +1,228 @@
+//! 记录索引
+//!
+//! 一次扫描得到每条记录的起始偏移与时间戳，之后可按序号随机访问；
+//! 索引可保存为与日志文件相邻的 sidecar 文件，下次打开时免去重新扫描。
+
+use std::fs;
+use std::ops::{Bound, Range, RangeBounds};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use crate::error::ParseError;
+
+/// sidecar 文件魔数与格式版本
+const MAGIC: &[u8; 8] = b"DMSQLIDX";
+const VERSION: u32 = 2;
+/// 头部：魔数 + 版本 + 文件长度 + mtime 秒 + mtime 纳秒 + 标志 + 数据长度 + 记录数
+const HEADER_LEN: usize = 8 + 4 + 8 + 8 + 4 + 4 + 8 + 8;
+/// 标志位：数据即磁盘文件原样内容（未解压、未转码），文件长度应等于数据长度
+const FLAG_MAPPED: u32 = 1;
+/// `timestamps` 中表示"时间戳无法解析"的值
+const NO_TS: i64 = i64::MIN;
+
+/// 记录起始字节偏移列表，由 `LogParser::index()` 一次性构建。
+/// 每个元素是某条记录在内存映射缓冲区内的绝对字节偏移。
+/// 用于两阶段并行扫描：先建索引，再按记录数均匀分区给多线程；
+/// 也可配合 [`LogParser::get`](crate::LogParser::get) 按序号随机访问。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordIndex {
+    pub(crate) offsets: Vec<usize>,
+    /// 与 `offsets` 一一对应的 UTC Unix 毫秒数，无法解析时为 `NO_TS`
+    pub(crate) timestamps: Vec<i64>,
+    /// 构建索引时的数据长度，即最后一条记录的结束位置
+    pub(crate) data_len: usize,
+    /// 构建索引时日志文件的长度与修改时间；解析器不是从文件打开时为 `None`
+    pub(crate) stamp: Option<FileStamp>,
+    /// 数据是否为磁盘文件的原样内容（内存映射，未解压、未转码）
+    pub(crate) mapped: bool,
+}
+
+/// 文件长度与修改时间（秒、纳秒）
+pub(crate) type FileStamp = (u64, u64, u32);
+
+impl RecordIndex {
+    /// 记录总数
+    pub fn len(&self) -> usize {
+        self.offsets.len()
+    }
+
+    /// 是否为空（文件不含任何完整记录）
+    pub fn is_empty(&self) -> bool {
+        self.offsets.is_empty()
+    }
+
+    /// 全部记录的起始字节偏移
+    pub fn offsets(&self) -> &[usize] {
+        &self.offsets
+    }
+
+    /// 第 `n` 条记录占据的字节范围；`n` 越界时返回 `None`
+    pub fn byte_range(&self, n: usize) -> Option<Range<usize>> {
+        let start = *self.offsets.get(n)?;
+        let end = self.offsets.get(n + 1).copied().unwrap_or(self.data_len);
+        Some(start..end)
+    }
+
+    /// 第 `n` 条记录的 UTC Unix 毫秒数
+    ///
+    /// 按构建索引时解析器的 [`utc_offset`](crate::LogParserBuilder::utc_offset) 换算，
+    /// 与 [`Sqllog::timestamp_millis`](crate::Sqllog::timestamp_millis) 一致。
+    pub fn timestamp_millis(&self, n: usize) -> Option<i64> {
+        self.timestamps.get(n).copied().filter(|&ts| ts != NO_TS)
+    }
+
+    /// 序号范围 `range` 对应的字节范围（越界部分截断到末尾）
+    pub(crate) fn span<R: RangeBounds<usize>>(&self, range: R) -> Range<usize> {
+        let len = self.len();
+        let start = match range.start_bound() {
+            Bound::Included(&s) => s,
+            Bound::Excluded(&s) => s.saturating_add(1),
+            Bound::Unbounded => 0,
+        }
+        .min(len);
+        let end = match range.end_bound() {
+            Bound::Included(&e) => e.saturating_add(1),
+            Bound::Excluded(&e) => e,
+            Bound::Unbounded => len,
+        }
+        .clamp(start, len);
+        let at = |n: usize| self.offsets.get(n).copied().unwrap_or(self.data_len);
+        at(start)..at(end)
+    }
+
+    /// 日志文件默认的 sidecar 索引路径（在原文件名后追加 `.idx`）
+    pub fn sidecar_path<P: AsRef<Path>>(log_path: P) -> PathBuf {
+        let mut path = log_path.as_ref().as_os_str().to_owned();
+        path.push(".idx");
+        PathBuf::from(path)
+    }
+
+    /// 将索引写入 `index_path`，并记录构建索引（`LogParser::index()`）时日志文件的长度与修改时间。
+    ///
+    /// 索引由内存数据（`LogParser::from_bytes` 等）构建时，改为记录 `log_path` 当前的
+    /// 长度与修改时间，由调用方保证数据与该文件一致。
+    /// 先写临时文件再重命名，避免中途崩溃留下半截内容。
+    pub fn save<P: AsRef<Path>, L: AsRef<Path>>(
+        &self,
+        index_path: P,
+        log_path: L,
+    ) -> Result<(), ParseError> {
+        let (file_len, secs, nanos) = match self.stamp {
+            Some(stamp) => stamp,
+            None => file_stamp(log_path.as_ref())?,
+        };
+        let flags = if self.mapped { FLAG_MAPPED } else { 0 };
+
+        let mut buf = Vec::with_capacity(HEADER_LEN + self.len() * 16);
+        buf.extend_from_slice(MAGIC);
+        buf.extend_from_slice(&VERSION.to_le_bytes());
+        buf.extend_from_slice(&file_len.to_le_bytes());
+        buf.extend_from_slice(&secs.to_le_bytes());
+        buf.extend_from_slice(&nanos.to_le_bytes());
+        buf.extend_from_slice(&flags.to_le_bytes());
+        buf.extend_from_slice(&(self.data_len as u64).to_le_bytes());
+        buf.extend_from_slice(&(self.len() as u64).to_le_bytes());
+        for (&offset, &ts) in self.offsets.iter().zip(&self.timestamps) {
+            buf.extend_from_slice(&(offset as u64).to_le_bytes());
+            buf.extend_from_slice(&ts.to_le_bytes());
+        }
+
+        let index_path = index_path.as_ref();
+        let mut tmp = index_path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        fs::write(&tmp, buf).map_err(|e| ParseError::IoError(e.to_string()))?;
+        fs::rename(&tmp, index_path).map_err(|e| ParseError::IoError(e.to_string()))
+    }
+
+    /// 读取 `index_path` 处的索引，并校验 `log_path` 的长度与修改时间自构建索引以来未变；
+    /// 未压缩的日志文件还要求文件长度等于索引的数据长度。
+    ///
+    /// 日志文件已变化时返回 [`ParseError::IndexMismatch`]，此时应重新调用
+    /// `LogParser::index()` 构建；索引文件损坏时返回 [`ParseError::InvalidFormat`]。
+    pub fn load<P: AsRef<Path>, L: AsRef<Path>>(
+        index_path: P,
+        log_path: L,
+    ) -> Result<Self, ParseError> {
+        let index_path = index_path.as_ref();
+        let log_path = log_path.as_ref();
+        let bytes = fs::read(index_path).map_err(|e| ParseError::IoError(e.to_string()))?;
+        let corrupt = |reason: &str| ParseError::InvalidFormat {
+            raw: format!("{}: {reason}", index_path.display()),
+        };
+
+        if bytes.len() < HEADER_LEN || &bytes[..8] != MAGIC {
+            return Err(corrupt("not a record index file"));
+        }
+        let mut reader = FieldReader { bytes: &bytes[8..] };
+        if reader.u32() != VERSION {
+            return Err(corrupt("unsupported index version"));
+        }
+        let stamp = (reader.u64(), reader.u64(), reader.u32());
+        let mapped = reader.u32() & FLAG_MAPPED != 0;
+        let data_len = reader.u64() as usize;
+        let count = reader.u64() as usize;
+        if reader.bytes.len() != count.saturating_mul(16) {
+            return Err(corrupt("truncated index file"));
+        }
+
+        let mismatch = |reason: &str| ParseError::IndexMismatch {
+            path: log_path.display().to_string(),
+            reason: reason.to_string(),
+        };
+        if file_stamp(log_path)? != stamp {
+            return Err(mismatch("file size or modification time changed"));
+        }
+        if mapped && stamp.0 != data_len as u64 {
+            return Err(mismatch("file size differs from indexed data length"));
+        }
+
+        let mut offsets = Vec::with_capacity(count);
+        let mut timestamps = Vec::with_capacity(count);
+        for _ in 0..count {
+            offsets.push(reader.u64() as usize);
+            timestamps.push(reader.u64() as i64);
+        }
+        if offsets.windows(2).any(|w| w[0] >= w[1]) || offsets.last() > Some(&data_len) {
+            return Err(corrupt("offsets out of order"));
+        }
+        Ok(Self {
+            offsets,
+            timestamps,
+            data_len,
+            stamp: Some(stamp),
+            mapped,
+        })
+    }
+}
+
+/// 文件长度与修改时间（秒、纳秒）
+pub(crate) fn file_stamp(path: &Path) -> Result<FileStamp, ParseError> {
+    let meta = fs::metadata(path).map_err(|e| ParseError::IoError(e.to_string()))?;
+    let mtime = meta
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .unwrap_or_default();
+    Ok((meta.len(), mtime.as_secs(), mtime.subsec_nanos()))
+}
+
+/// 顺序读取小端定长字段；调用方已校验长度
+struct FieldReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl FieldReader<'_> {
+    fn take<const N: usize>(&mut self) -> [u8; N] {
+        let (head, rest) = self.bytes.split_at(N);
+        self.bytes = rest;
+        head.try_into().expect("length checked by caller")
+    }
+
+    fn u32(&mut self) -> u32 {
+        u32::from_le_bytes(self.take())
+    }
+
+    fn u64(&mut self) -> u64 {
+        u64::from_le_bytes(self.take())
+    }
+}
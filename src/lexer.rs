@@ -0,0 +1,230 @@
+//! 轻量 SQL 词法分析
+//!
+//! 只切分 token，不做语法分析；覆盖 DM / Oracle 常见写法：`''` 转义的字符串、
+//! `N'..'` / `X'..'` / `q'[..]'` 字面量、`""` 转义的带引号标识符、`?` / `:name` 绑定参数
+//! 以及 `--` / `/* */` 注释。非 ASCII 字节视为标识符的一部分，因此 token 边界总落在字符边界上。
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TokenKind {
+    /// 关键字或未加引号的标识符
+    Word,
+    /// `"..."` 标识符（含引号）
+    QuotedIdent,
+    /// 字符串字面量（含前缀与引号）
+    String,
+    Number,
+    /// `?`、`:name`、`:1`
+    Param,
+    /// 运算符与标点
+    Punct,
+    /// `--` 或 `/* */` 注释
+    Comment,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Token<'s> {
+    pub(crate) kind: TokenKind,
+    pub(crate) text: &'s str,
+}
+
+impl Token<'_> {
+    /// 是否为与 `word` 大小写无关相等的关键字 / 标识符
+    pub(crate) fn is_word(&self, word: &str) -> bool {
+        self.kind == TokenKind::Word && self.text.eq_ignore_ascii_case(word)
+    }
+
+    pub(crate) fn is_punct(&self, punct: &str) -> bool {
+        self.kind == TokenKind::Punct && self.text == punct
+    }
+
+    /// 是否为字面量或绑定参数
+    pub(crate) fn is_value(&self) -> bool {
+        matches!(
+            self.kind,
+            TokenKind::String | TokenKind::Number | TokenKind::Param
+        )
+    }
+}
+
+/// 产出 token 的迭代器，跳过空白；注释作为 [`TokenKind::Comment`] 产出
+pub(crate) struct Lexer<'s> {
+    src: &'s str,
+    pos: usize,
+}
+
+impl<'s> Lexer<'s> {
+    pub(crate) fn new(src: &'s str) -> Self {
+        Self { src, pos: 0 }
+    }
+
+    /// 跳过注释的 token 序列
+    pub(crate) fn code(src: &'s str) -> impl Iterator<Item = Token<'s>> {
+        Self::new(src).filter(|t| t.kind != TokenKind::Comment)
+    }
+
+    fn peek(&self, ahead: usize) -> u8 {
+        self.src
+            .as_bytes()
+            .get(self.pos + ahead)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    fn eat_while(&mut self, f: impl Fn(u8) -> bool) {
+        let bytes = self.src.as_bytes();
+        while self.pos < bytes.len() && f(bytes[self.pos]) {
+            self.pos += 1;
+        }
+    }
+
+    /// 从 `quote` 处开始消费，直到未被加倍转义的同一引号
+    fn eat_quoted(&mut self, quote: u8) {
+        let bytes = self.src.as_bytes();
+        self.pos += 1;
+        while self.pos < bytes.len() {
+            if bytes[self.pos] == quote {
+                if self.peek(1) == quote {
+                    self.pos += 2;
+                    continue;
+                }
+                self.pos += 1;
+                return;
+            }
+            self.pos += 1;
+        }
+    }
+
+    /// Oracle 风格 `q'<d>...<d>'`，`self.pos` 指向 `'`。
+    ///
+    /// 定界符须为可见 ASCII 字符；定界符不合法或找不到对应的 `<d>'` 时，
+    /// 按普通 `'...'` 字符串处理，避免吞掉其后的全部语句。
+    fn eat_q_quoted(&mut self) {
+        let open = self.peek(1);
+        let close = match open {
+            b'[' => b']',
+            b'{' => b'}',
+            b'(' => b')',
+            b'<' => b'>',
+            c if c.is_ascii_graphic() && c != b'\'' => c,
+            _ => return self.eat_quoted(b'\''),
+        };
+        let bytes = self.src.as_bytes();
+        let mut i = self.pos + 2;
+        while i + 1 < bytes.len() {
+            if bytes[i] == close && bytes[i + 1] == b'\'' {
+                self.pos = i + 2;
+                return;
+            }
+            i += 1;
+        }
+        self.eat_quoted(b'\'')
+    }
+
+    fn eat_number(&mut self) {
+        let bytes = self.src.as_bytes();
+        if bytes[self.pos] == b'0' && matches!(self.peek(1), b'x' | b'X') {
+            self.pos += 2;
+            self.eat_while(|b| b.is_ascii_hexdigit());
+            return;
+        }
+        self.eat_while(|b| b.is_ascii_digit());
+        if self.peek(0) == b'.' && self.peek(1).is_ascii_digit() {
+            self.pos += 1;
+            self.eat_while(|b| b.is_ascii_digit());
+        }
+        if matches!(self.peek(0), b'e' | b'E') {
+            let sign = usize::from(matches!(self.peek(1), b'+' | b'-'));
+            if self.peek(1 + sign).is_ascii_digit() {
+                self.pos += 1 + sign;
+                self.eat_while(|b| b.is_ascii_digit());
+            }
+        }
+    }
+}
+
+fn is_ident_start(b: u8) -> bool {
+    b.is_ascii_alphabetic() || b == b'_' || b >= 0x80
+}
+
+fn is_ident_char(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || matches!(b, b'_' | b'$' | b'#') || b >= 0x80
+}
+
+impl<'s> Iterator for Lexer<'s> {
+    type Item = Token<'s>;
+
+    fn next(&mut self) -> Option<Token<'s>> {
+        self.eat_while(|b| b.is_ascii_whitespace());
+        let start = self.pos;
+        let b = self.peek(0);
+        if start >= self.src.len() {
+            return None;
+        }
+
+        let kind = match b {
+            b'-' if self.peek(1) == b'-' => {
+                self.eat_while(|b| b != b'\n');
+                TokenKind::Comment
+            }
+            b'/' if self.peek(1) == b'*' => {
+                self.pos = self.src[start + 2..]
+                    .find("*/")
+                    .map_or(self.src.len(), |i| start + 2 + i + 2);
+                TokenKind::Comment
+            }
+            b'\'' => {
+                self.eat_quoted(b'\'');
+                TokenKind::String
+            }
+            b'"' => {
+                self.eat_quoted(b'"');
+                TokenKind::QuotedIdent
+            }
+            b'0'..=b'9' => {
+                self.eat_number();
+                TokenKind::Number
+            }
+            b'.' if self.peek(1).is_ascii_digit() => {
+                self.eat_number();
+                TokenKind::Number
+            }
+            b'?' => {
+                self.pos += 1;
+                TokenKind::Param
+            }
+            b':' if is_ident_char(self.peek(1)) => {
+                self.pos += 1;
+                self.eat_while(is_ident_char);
+                TokenKind::Param
+            }
+            b if is_ident_start(b) => {
+                self.pos += 1;
+                if self.peek(0) == b'\'' && matches!(b, b'n' | b'N' | b'x' | b'X' | b'b' | b'B') {
+                    self.eat_quoted(b'\'');
+                    TokenKind::String
+                } else if self.peek(0) == b'\'' && matches!(b, b'q' | b'Q') {
+                    self.eat_q_quoted();
+                    TokenKind::String
+                } else {
+                    self.eat_while(is_ident_char);
+                    TokenKind::Word
+                }
+            }
+            _ => {
+                let two = &self.src.as_bytes()[start..(start + 2).min(self.src.len())];
+                let len = match two {
+                    b"<=" | b">=" | b"<>" | b"!=" | b"||" | b":=" | b"=>" => 2,
+                    // 多字节 UTF-8 字符已由标识符分支处理，这里只剩单字节
+                    _ => 1,
+                };
+                self.pos += len;
+                TokenKind::Punct
+            }
+        };
+
+        Some(Token {
+            kind,
+            text: &self.src[start..self.pos],
+        })
+    }
+}
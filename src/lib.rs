@@ -66,10 +66,55 @@
 //! SELECT * FROM table EXECTIME: 10.5(ms) ROWCOUNT: 100(rows) EXEC_ID: 12345.
 //! ```
 
+#[cfg(feature = "arrow")]
+pub(crate) mod arrow;
+pub(crate) mod charset;
+pub(crate) mod checkpoint;
+pub(crate) mod chunks;
+pub(crate) mod columnar;
+pub(crate) mod compress;
 pub(crate) mod error;
+pub(crate) mod export;
+pub(crate) mod fingerprint;
+pub(crate) mod follow;
+pub(crate) mod index;
+pub(crate) mod lexer;
+pub(crate) mod logset;
+#[cfg(feature = "parquet")]
+pub(crate) mod parquet;
 pub(crate) mod parser;
+#[cfg(feature = "sqlite")]
+pub(crate) mod sqlite;
 pub(crate) mod sqllog;
+pub(crate) mod statement;
+pub(crate) mod stream;
+pub(crate) mod tables;
+pub(crate) mod tag;
+pub(crate) mod timestamp;
+#[cfg(feature = "serde")]
+pub(crate) mod view;
 
+#[cfg(feature = "arrow")]
+pub use arrow::{ArrowBatchBuilder, ArrowBatches, sqllog_schema};
+pub use charset::{DecodePolicy, DecodeStats, Encoding};
+pub use checkpoint::{Checkpoint, FileIdentity};
+pub use chunks::{ParChunks, RecordChunk};
+pub use columnar::{Batches, ColumnBatch};
 pub use error::ParseError;
-pub use parser::{LogIterator, LogParser, RecordIndex, parse_record};
+pub use export::{Column, CsvWriter, ExportStats, JsonLinesWriter};
+pub use fingerprint::{Fingerprint, fingerprint};
+pub use follow::LogFollower;
+pub use index::RecordIndex;
+pub use logset::LogSet;
+#[cfg(feature = "parquet")]
+pub use parquet::{ParquetCompression, ParquetWriter, Partitioning};
+pub use parser::{LogIterator, LogParser, LogParserBuilder, RangeIter, parse_record};
+#[cfg(feature = "sqlite")]
+pub use sqlite::SqliteSink;
 pub use sqllog::{MetaParts, PerformanceMetrics, Sqllog};
+pub use statement::StatementKind;
+pub use stream::StreamingLogParser;
+pub use tables::{TableAccess, TableRef, referenced_tables};
+pub use tag::SqllogTag;
+#[cfg(feature = "serde")]
+pub use view::SqllogView;
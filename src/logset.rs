@@ -0,0 +1,335 @@
+//! 多文件日志集合：按时间顺序解析一个目录（或通配符匹配）下的全部轮转 sqllog 文件。
+
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::error::ParseError;
+use crate::follow::parse_dm_file_name;
+use crate::parser::{LogParser, LogParserBuilder};
+use crate::sqllog::Sqllog;
+use crate::stream::StreamingLogParser;
+
+/// 压缩归档后缀；排序与过滤时先去掉这些后缀再识别 DM 文件名
+const COMPRESSED_SUFFIXES: [&str; 3] = [".gz", ".zst", ".xz"];
+
+/// 排序时为寻找首条记录最多读取的（解压后）字节数
+const PROBE_BYTES: u64 = 64 * 1024;
+
+/// 一组按时间顺序排列的 sqllog 文件
+///
+/// 排序依据依次为：DM 文件名 `dmsql_<inst>_<yyyymmdd>_<hhmmss>.log` 中的时间、
+/// 文件首条记录的时间戳、文件路径。每条记录（包括解析错误）都附带其来源文件路径。
+///
+/// 构建时只记录路径，文件在迭代到它时才打开，读完即释放，因此压缩归档不会同时
+/// 解压驻留内存。打开失败的文件在迭代时产出一条 `Err`。只有文件名不含时间的文件
+/// 会在构建时以流式方式读取首条记录（最多读取开头 64 KiB）用于排序；此时无法打开的文件排在最后，
+/// 错误同样在迭代时产出。
+///
+/// ```rust,no_run
+/// use dm_database_parser_sqllog::LogSet;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let set = LogSet::from_glob("/dm/log/dmsql_DMSERVER_20250812_*.log")?;
+/// for (path, result) in set.iter() {
+///     if let Err(e) = result {
+///         eprintln!("{}: {}", path.display(), e);
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct LogSet {
+    files: Vec<PathBuf>,
+    builder: LogParserBuilder,
+}
+
+impl LogSet {
+    /// 收集目录下所有 `*.log` 文件（含 `.log.gz` / `.log.zst` / `.log.xz` 归档），不递归子目录
+    pub fn from_dir<P: AsRef<Path>>(dir: P) -> Result<Self, ParseError> {
+        Self::from_paths(list_dir(dir.as_ref(), |name| {
+            strip_compressed_suffix(name).ends_with(".log")
+        })?)
+    }
+
+    /// 按通配符收集文件，如 `logs/dmsql_DMSERVER_20250812_*.log`。
+    ///
+    /// 仅文件名部分支持 `*` 与 `?`，目录部分按字面路径处理。
+    pub fn from_glob(pattern: &str) -> Result<Self, ParseError> {
+        let pattern_path = Path::new(pattern);
+        let file_pattern = pattern_path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .ok_or_else(|| ParseError::FileNotFound {
+                path: pattern.to_string(),
+            })?;
+        let dir = match pattern_path.parent() {
+            Some(p) if !p.as_os_str().is_empty() => p,
+            _ => Path::new("."),
+        };
+        Self::from_paths(list_dir(dir, |name| {
+            wildcard_match(file_pattern.as_bytes(), name.as_bytes())
+        })?)
+    }
+
+    /// 从显式给定的文件列表构建（会重新按时间排序）
+    pub fn from_paths<I, P>(paths: I) -> Result<Self, ParseError>
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        let mut keyed: Vec<(Option<u64>, PathBuf)> = Vec::new();
+        for path in paths {
+            let path = path.as_ref().to_path_buf();
+            let key = match name_sort_key(&path) {
+                Some(key) => Some(key),
+                // 打开失败的文件不影响整个集合，错误留到迭代时产出
+                None => first_record_sort_key(&path).unwrap_or(None),
+            };
+            keyed.push((key, path));
+        }
+        // 无法确定时间的文件排在最后；时间相同时按路径排序，保证结果确定
+        keyed.sort_by(|(ka, a), (kb, b)| (ka.is_none(), ka, a).cmp(&(kb.is_none(), kb, b)));
+        Ok(Self {
+            files: keyed.into_iter().map(|(_, f)| f).collect(),
+            builder: LogParserBuilder::new(),
+        })
+    }
+
+    /// 以 `builder` 中的选项（编码、解码策略、时区、标签）打开每个文件，
+    /// 等价于对每个文件调用 [`LogParserBuilder::open`]。
+    ///
+    /// ```rust,no_run
+    /// use dm_database_parser_sqllog::{Encoding, LogParser, LogSet};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let set = LogSet::from_dir("/dm/log")?.options(
+    ///     LogParser::builder()
+    ///         .encoding(Encoding::Gbk)
+    ///         .utc_offset(8 * 3600),
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn options(mut self, builder: LogParserBuilder) -> Self {
+        self.builder = builder;
+        self
+    }
+
+    /// 文件数量
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    /// 是否不含任何文件
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// 按时间顺序返回各文件路径
+    pub fn paths(&self) -> impl Iterator<Item = &Path> + '_ {
+        self.files.iter().map(PathBuf::as_path)
+    }
+
+    /// 按时间顺序逐个打开文件，产出各自的 [`LogParser`]。
+    ///
+    /// 每个解析器在迭代器前进到下一个文件前由调用方持有，适合需要零拷贝借用记录、
+    /// 或在文件内部使用 [`LogParser::par_iter`] 的场景。
+    pub fn parsers(&self) -> impl Iterator<Item = (&Path, Result<LogParser, ParseError>)> + '_ {
+        self.files
+            .iter()
+            .map(|path| (path.as_path(), self.builder.clone().open(path)))
+    }
+
+    /// 按文件时间顺序依次迭代所有记录，每项附带来源文件路径。
+    ///
+    /// 同一时刻只打开一个文件；记录脱离文件缓冲区（`Sqllog<'static>`）后产出。
+    pub fn iter(&self) -> impl Iterator<Item = (&Path, Result<Sqllog<'static>, ParseError>)> + '_ {
+        self.files.iter().flat_map(|path| {
+            FileRecords::new(path, &self.builder).map(move |r| (path.as_path(), r))
+        })
+    }
+
+    /// 并行迭代所有记录：文件之间并行，大文件内部再按 [`LogParser::par_iter`]
+    /// 的方式在记录边界处分块并行解析。
+    ///
+    /// 结果顺序不确定。文件在其全部分块解析完毕后释放。
+    pub fn par_iter(
+        &self,
+    ) -> impl rayon::iter::ParallelIterator<Item = (&Path, Result<Sqllog<'static>, ParseError>)> + '_
+    {
+        use rayon::iter::Either;
+        use rayon::prelude::*;
+
+        self.files.par_iter().flat_map(|path| {
+            let parts: Vec<Result<FilePart, ParseError>> = match self.builder.clone().open(path) {
+                Ok(parser) => {
+                    let parser = Arc::new(parser);
+                    parser
+                        .par_bounds()
+                        .into_iter()
+                        .map(|(start, end)| Ok((Arc::clone(&parser), start, end)))
+                        .collect()
+                }
+                Err(e) => vec![Err(e)],
+            };
+            parts.into_par_iter().flat_map_iter(move |part| {
+                let records = match part {
+                    Ok((parser, start, end)) => Either::Left(OwnedRecords::new(parser, start, end)),
+                    Err(e) => Either::Right(std::iter::once(Err(e))),
+                };
+                records.map(move |r| (path.as_path(), r))
+            })
+        })
+    }
+}
+
+/// [`LogSet::par_iter`] 中单个文件的一个分区：共享的解析器与 `[start, end)` 字节范围
+type FilePart = (Arc<LogParser>, usize, usize);
+
+/// 单文件记录迭代器：首次 `next()` 时打开文件，耗尽后释放。
+struct FileRecords<'p> {
+    path: &'p Path,
+    builder: &'p LogParserBuilder,
+    records: Option<OwnedRecords>,
+    done: bool,
+}
+
+impl<'p> FileRecords<'p> {
+    fn new(path: &'p Path, builder: &'p LogParserBuilder) -> Self {
+        Self {
+            path,
+            builder,
+            records: None,
+            done: false,
+        }
+    }
+}
+
+impl Iterator for FileRecords<'_> {
+    type Item = Result<Sqllog<'static>, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        if self.records.is_none() {
+            match self.builder.clone().open(self.path) {
+                Ok(parser) => {
+                    let end = parser.as_bytes().len();
+                    self.records = Some(OwnedRecords::new(Arc::new(parser), 0, end));
+                }
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            }
+        }
+        let next = self.records.as_mut()?.next();
+        if next.is_none() {
+            self.done = true;
+            self.records = None;
+        }
+        next
+    }
+}
+
+/// 持有解析器的记录迭代器：产出 `[start, end)` 范围内的记录，转为 `Sqllog<'static>`。
+///
+/// 只保存下一条记录的偏移，每次 `next()` 从解析器重新借出迭代器，无需自引用。
+struct OwnedRecords {
+    parser: Arc<LogParser>,
+    pos: usize,
+    end: usize,
+}
+
+impl OwnedRecords {
+    fn new(parser: Arc<LogParser>, start: usize, end: usize) -> Self {
+        Self {
+            parser,
+            pos: start,
+            end,
+        }
+    }
+}
+
+impl Iterator for OwnedRecords {
+    type Item = Result<Sqllog<'static>, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut iter = self.parser.span(self.pos, self.end);
+        let next = iter.next()?;
+        self.pos = iter.position();
+        Some(next.map(Sqllog::into_owned))
+    }
+}
+
+fn list_dir(dir: &Path, filter: impl Fn(&str) -> bool) -> Result<Vec<PathBuf>, ParseError> {
+    let entries = fs::read_dir(dir).map_err(|e| ParseError::IoError(e.to_string()))?;
+    let mut paths = Vec::new();
+    for entry in entries {
+        let entry = entry.map_err(|e| ParseError::IoError(e.to_string()))?;
+        let path = entry.path();
+        // Path::is_file 跟随符号链接，指向日志文件的链接同样收集
+        if entry.file_name().to_str().is_some_and(&filter) && path.is_file() {
+            paths.push(path);
+        }
+    }
+    Ok(paths)
+}
+
+fn strip_compressed_suffix(name: &str) -> &str {
+    COMPRESSED_SUFFIXES
+        .iter()
+        .find_map(|suffix| name.strip_suffix(suffix))
+        .unwrap_or(name)
+}
+
+/// DM 文件名中的时间，统一为 `yyyymmddhhmmssmmm` 以便与记录时间戳比较
+fn name_sort_key(path: &Path) -> Option<u64> {
+    let name = path.file_name()?.to_str()?;
+    parse_dm_file_name(strip_compressed_suffix(name)).map(|(_, stamp)| stamp * 1000)
+}
+
+/// 首条成功解析记录的时间戳，转换为 `yyyymmddhhmmssmmm`。
+///
+/// 以流式方式读取（压缩归档边读边解压），最多读取 [`PROBE_BYTES`]，
+/// 因此开头不是有效记录的大文件不会被整个扫描。
+fn first_record_sort_key(path: &Path) -> Result<Option<u64>, ParseError> {
+    let head = StreamingLogParser::from_path(path)?
+        .into_inner()
+        .take(PROBE_BYTES);
+    let Some(record) = StreamingLogParser::new(head).find_map(Result::ok) else {
+        return Ok(None);
+    };
+    Ok(record
+        .ts
+        .bytes()
+        .filter(u8::is_ascii_digit)
+        .try_fold(0u64, |acc, b| {
+            acc.checked_mul(10)?.checked_add(u64::from(b - b'0'))
+        }))
+}
+
+/// 简单通配符匹配：`*` 匹配任意长度（含空），`?` 匹配单个字节
+fn wildcard_match(pattern: &[u8], name: &[u8]) -> bool {
+    let (mut p, mut n) = (0usize, 0usize);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == b'?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == b'*' {
+            backtrack = Some((p, n));
+            p += 1;
+        } else if let Some((bp, bn)) = backtrack {
+            p = bp + 1;
+            n = bn + 1;
+            backtrack = Some((bp, bn + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&b| b == b'*')
+}
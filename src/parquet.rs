@@ -0,0 +1,262 @@
+//! Parquet 归档（需启用 `parquet` feature）
+//!
+//! 基于 [`ArrowBatchBuilder`] 把记录流式写入 Parquet 文件，schema 与
+//! [`sqllog_schema`](crate::sqllog_schema) 一致，可被任意 Parquet 引擎直接查询。
+
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+
+use parquet::arrow::ArrowWriter;
+use parquet::basic::{Compression, ZstdLevel};
+use parquet::file::properties::WriterProperties;
+
+use crate::arrow::{ArrowBatchBuilder, sqllog_schema};
+use crate::error::ParseError;
+use crate::sqllog::Sqllog;
+
+/// 每个分区在内存中累积多少行后交给 `ArrowWriter`
+const BATCH_ROWS: usize = 8192;
+
+/// 默认同时打开的分区写入器上限
+const DEFAULT_OPEN_PARTITIONS: usize = 16;
+
+/// 列压缩算法
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ParquetCompression {
+    Uncompressed,
+    /// 默认；压缩比与速度较均衡
+    #[default]
+    Snappy,
+    /// 指定压缩级别（1–22），适合长期归档
+    Zstd(i32),
+}
+
+/// 按 `Sqllog::ts`（服务器本地时间）划分输出目录
+///
+/// 采用 Hive 风格目录名，Spark、DuckDB、DataFusion 等可直接识别分区列。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Partitioning {
+    /// 不分区，所有记录写入单个文件
+    #[default]
+    None,
+    /// `<dir>/date=YYYY-MM-DD/part-N.parquet`
+    Date,
+    /// `<dir>/date=YYYY-MM-DD/hour=HH/part-N.parquet`
+    Hour,
+}
+
+struct Partition {
+    /// 同一分区内的文件序号（`part-N.parquet` 的 `N`）
+    part: usize,
+    path: PathBuf,
+    writer: ArrowWriter<File>,
+    builder: ArrowBatchBuilder,
+    /// 最近一次写入时的 [`ParquetWriter::clock`]，用于淘汰最久未用的分区
+    last_used: u64,
+}
+
+impl Partition {
+    fn flush(&mut self) -> Result<(), ParseError> {
+        if self.builder.is_empty() {
+            return Ok(());
+        }
+        let batch = self.builder.finish()?;
+        self.writer.write(&batch).map_err(export_error)
+    }
+
+    /// 写出缓冲数据与文件尾
+    fn close(mut self) -> Result<PathBuf, ParseError> {
+        self.flush()?;
+        self.writer.close().map_err(export_error)?;
+        Ok(self.path)
+    }
+}
+
+fn export_error(e: parquet::errors::ParquetError) -> ParseError {
+    ParseError::ExportError(e.to_string())
+}
+
+/// 把解析后的记录流式写入 Parquet 文件
+///
+/// - 不分区时 `path` 即输出文件；按日期 / 小时分区时 `path` 为根目录，
+///   分区文件在首次写入该分区的记录时创建。已存在的同名文件会被覆盖。
+/// - 每个分区最多缓冲 [`row_group_size`](Self::row_group_size) 行后写出一个
+///   row group，乱序记录会写入各自所属的分区。
+/// - 同时打开的分区超过 [`max_open_partitions`](Self::max_open_partitions) 时，
+///   最久未写入的分区被关闭；之后再有记录落入该分区时写入新的 `part-N.parquet`。
+///   按时间顺序的日志因此只保留当前分区的缓冲，内存不随分区数增长。
+/// - 必须调用 [`finish`](Self::finish) 写入文件尾，否则文件不完整。
+///
+/// ```rust,no_run
+/// use dm_database_parser_sqllog::{LogParser, ParquetCompression, ParquetWriter, Partitioning};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let parser = LogParser::from_path("sqllog.txt")?;
+/// let mut writer = ParquetWriter::create("archive")
+///     .partition_by(Partitioning::Hour)
+///     .compression(ParquetCompression::Zstd(9));
+/// for record in parser.iter().filter_map(Result::ok) {
+///     writer.write(&record)?;
+/// }
+/// for path in writer.finish()? {
+///     println!("{}", path.display());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct ParquetWriter {
+    root: PathBuf,
+    partitioning: Partitioning,
+    compression: ParquetCompression,
+    row_group_size: usize,
+    max_open: usize,
+    partitions: BTreeMap<String, Partition>,
+    /// 已关闭的文件，键为 (分区, 文件序号)
+    closed: BTreeMap<(String, usize), PathBuf>,
+    clock: u64,
+    rows: usize,
+}
+
+impl ParquetWriter {
+    /// 创建写入器；文件与目录在写入第一条记录时才创建
+    pub fn create<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            root: path.as_ref().to_path_buf(),
+            partitioning: Partitioning::None,
+            compression: ParquetCompression::default(),
+            row_group_size: 1024 * 1024,
+            max_open: DEFAULT_OPEN_PARTITIONS,
+            partitions: BTreeMap::new(),
+            closed: BTreeMap::new(),
+            clock: 0,
+            rows: 0,
+        }
+    }
+
+    /// 每个 row group 的最大行数（默认 1 048 576，最小 1）
+    pub fn row_group_size(mut self, rows: usize) -> Self {
+        self.row_group_size = rows.max(1);
+        self
+    }
+
+    /// 同时打开的分区写入器上限（默认 16，最小 1）
+    pub fn max_open_partitions(mut self, partitions: usize) -> Self {
+        self.max_open = partitions.max(1);
+        self
+    }
+
+    /// 列压缩算法（默认 Snappy）
+    pub fn compression(mut self, compression: ParquetCompression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// 分区方式（默认不分区）
+    pub fn partition_by(mut self, partitioning: Partitioning) -> Self {
+        self.partitioning = partitioning;
+        self
+    }
+
+    /// 已写入的记录数
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// 写入一条记录
+    pub fn write(&mut self, record: &Sqllog<'_>) -> Result<(), ParseError> {
+        let key = self.partition_key(&record.ts);
+        if !self.partitions.contains_key(&key) {
+            if self.partitions.len() >= self.max_open {
+                self.close_least_recent()?;
+            }
+            let partition = self.open_partition(&key)?;
+            self.partitions.insert(key.clone(), partition);
+        }
+        self.clock += 1;
+        let partition = self.partitions.get_mut(&key).expect("partition is open");
+        partition.last_used = self.clock;
+        partition.builder.push(record);
+        if partition.builder.len() >= BATCH_ROWS.min(self.row_group_size) {
+            partition.flush()?;
+        }
+        self.rows += 1;
+        Ok(())
+    }
+
+    /// 写出缓冲数据与文件尾，按分区顺序返回生成的文件路径
+    pub fn finish(mut self) -> Result<Vec<PathBuf>, ParseError> {
+        for (key, partition) in std::mem::take(&mut self.partitions) {
+            let part = partition.part;
+            self.closed.insert((key, part), partition.close()?);
+        }
+        Ok(self.closed.into_values().collect())
+    }
+
+    fn close_least_recent(&mut self) -> Result<(), ParseError> {
+        let Some(key) = self
+            .partitions
+            .iter()
+            .min_by_key(|(_, p)| p.last_used)
+            .map(|(key, _)| key.clone())
+        else {
+            return Ok(());
+        };
+        let partition = self.partitions.remove(&key).expect("partition is open");
+        let part = partition.part;
+        self.closed.insert((key, part), partition.close()?);
+        Ok(())
+    }
+
+    /// 分区相对目录；`ts` 格式为 `YYYY-MM-DD HH:MM:SS.mmm`
+    fn partition_key(&self, ts: &str) -> String {
+        let date = ts.get(..10).unwrap_or(ts);
+        match self.partitioning {
+            Partitioning::None => String::new(),
+            Partitioning::Date => format!("date={date}"),
+            Partitioning::Hour => {
+                format!("date={date}/hour={}", ts.get(11..13).unwrap_or("00"))
+            }
+        }
+    }
+
+    fn open_partition(&self, key: &str) -> Result<Partition, ParseError> {
+        // 分区此前被关闭过时接着编号，不覆盖已写出的文件
+        let part = self
+            .closed
+            .range((key.to_owned(), 0)..=(key.to_owned(), usize::MAX))
+            .next_back()
+            .map_or(0, |((_, part), _)| part + 1);
+        let path = if key.is_empty() {
+            self.root.clone()
+        } else {
+            self.root.join(key).join(format!("part-{part}.parquet"))
+        };
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            fs::create_dir_all(dir).map_err(|e| ParseError::IoError(e.to_string()))?;
+        }
+
+        let compression = match self.compression {
+            ParquetCompression::Uncompressed => Compression::UNCOMPRESSED,
+            ParquetCompression::Snappy => Compression::SNAPPY,
+            ParquetCompression::Zstd(level) => {
+                Compression::ZSTD(ZstdLevel::try_new(level).map_err(export_error)?)
+            }
+        };
+        let props = WriterProperties::builder()
+            .set_max_row_group_row_count(Some(self.row_group_size))
+            .set_compression(compression)
+            .build();
+
+        let file = File::create(&path).map_err(|e| ParseError::IoError(e.to_string()))?;
+        let writer =
+            ArrowWriter::try_new(file, sqllog_schema(), Some(props)).map_err(export_error)?;
+        Ok(Partition {
+            part,
+            path,
+            writer,
+            builder: ArrowBatchBuilder::new(),
+            last_used: 0,
+        })
+    }
+}
@@ -6,13 +6,24 @@ use memmap2::Mmap;
 use simdutf8::basic::from_utf8 as simd_from_utf8;
 use std::borrow::Cow;
 use std::fs::File;
-use std::path::Path;
-use std::sync::LazyLock;
+use std::io::Read;
+use std::ops::{Deref, RangeBounds};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
 
+use crate::charset::{
+    DecodePolicy, DecodeStats, Encoding, encoding_name, invalid_offset, try_decode_legacy,
+};
+use crate::checkpoint::Checkpoint;
+use crate::chunks::ParChunks;
+use crate::columnar::Batches;
+use crate::compress::Compression;
 use crate::error::ParseError;
+use crate::index::{RecordIndex, file_stamp};
 use crate::sqllog::Sqllog;
-use encoding::all::GB18030;
-use encoding::{DecoderTrap, Encoding};
+use crate::tag::{SqllogTag, TagFilter};
+use crate::timestamp::{parse_bound, parse_ts_millis};
 
 /// Pre-built SIMD searcher for the `") "` meta-close pattern.
 /// Avoids rebuilding the Finder on every record parse.
@@ -20,88 +31,382 @@ static FINDER_CLOSE_META: LazyLock<Finder<'static>> = LazyLock::new(|| Finder::n
 
 /// Pre-built SIMD searcher for the `"\n20"` record-start pattern.
 /// Shared across threads via LazyLock; constructed once on first use.
-static FINDER_RECORD_START: LazyLock<Finder<'static>> = LazyLock::new(|| Finder::new(b"\n20"));
+pub(crate) static FINDER_RECORD_START: LazyLock<Finder<'static>> =
+    LazyLock::new(|| Finder::new(b"\n20"));
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
 pub(crate) enum FileEncodingHint {
     #[default]
     Auto,
+    /// 整个数据已校验为合法 UTF-8：跳过逐段校验直接借用
     Utf8,
+    /// 按 UTF-8 解析但数据未经整体校验（强制 UTF-8 且含非法字节，或自动探测仅采样判定）：
+    /// 逐段校验，非法部分按 U+FFFD 有损替换
+    Utf8Lossy,
     Gb18030,
+    Gbk,
+    Big5,
+    /// 逐条记录判定：整条记录为合法 UTF-8 则按 UTF-8，否则按 GB18030
+    PerRecord,
+}
+
+/// 逐记录解析选项：构建 `LogParser` 时确定，随迭代器传给每次 `parse_record_with_hint`
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub(crate) struct ParseOptions {
+    pub(crate) encoding: FileEncodingHint,
+    pub(crate) policy: DecodePolicy,
+    /// 服务器时区相对 UTC 的偏移（秒，东为正）
+    pub(crate) utc_offset: i32,
+    /// 作为标签提取的方括号标记
+    pub(crate) tags: TagFilter,
+}
+
+/// 日志数据的底层存储：文件内存映射，或调用方提供的内存缓冲区。
+///
+/// 由 `Arc` 共享，使后台解析任务（[`ParChunks`]）能在迭代器被提前丢弃时仍安全访问数据。
+pub(crate) enum Backing {
+    Mmap(Mmap),
+    Bytes(Box<dyn AsRef<[u8]> + Send + Sync>),
+}
+
+impl Deref for Backing {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &[u8] {
+        match self {
+            Backing::Mmap(mmap) => mmap,
+            Backing::Bytes(bytes) => (**bytes).as_ref(),
+        }
+    }
 }
 
 pub struct LogParser {
-    mmap: Mmap,
-    encoding: FileEncodingHint,
+    data: Arc<Backing>,
+    options: ParseOptions,
+    /// 由 [`LogParserBuilder::open`] 打开时的文件路径
+    path: Option<PathBuf>,
 }
 
-/// 记录起始字节偏移列表，由 `LogParser::index()` 一次性构建。
-/// 每个元素是某条记录在内存映射缓冲区内的绝对字节偏移。
-/// 用于两阶段并行扫描：先建索引，再按记录数均匀分区给多线程。
-pub struct RecordIndex {
-    pub(crate) offsets: Vec<usize>,
+/// [`LogParser`] 构建器，用于设置编码等解析选项。
+///
+/// ```rust,no_run
+/// use dm_database_parser_sqllog::{Encoding, LogParser};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let parser = LogParser::builder()
+///     .encoding(Encoding::Gb18030)
+///     .open("sqllog.txt")?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct LogParserBuilder {
+    encoding: Encoding,
+    candidates: Vec<Encoding>,
+    policy: DecodePolicy,
+    utc_offset: i32,
+    tags: TagFilter,
 }
 
-impl RecordIndex {
-    /// 记录总数
-    pub fn len(&self) -> usize {
-        self.offsets.len()
+impl LogParserBuilder {
+    pub fn new() -> Self {
+        Self::default()
     }
 
-    /// 是否为空（文件不含任何完整记录）
-    pub fn is_empty(&self) -> bool {
-        self.offsets.is_empty()
+    /// 设置文件编码（默认 [`Encoding::AutoSampled`]）
+    pub fn encoding(mut self, encoding: Encoding) -> Self {
+        self.encoding = encoding;
+        self
     }
-}
 
-impl LogParser {
-    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, ParseError> {
+    /// 自动探测模式下，非 UTF-8 文件的候选遗留编码（`Gb18030`、`Gbk`、`Gb2312`、`Big5`）。
+    ///
+    /// 默认仅 GB18030。给出多个候选时按启发式打分选择最可能的一个，
+    /// 分数相同时取靠前者；确知编码时建议直接用 [`encoding`](Self::encoding) 指定。
+    pub fn candidate_encodings<I>(mut self, candidates: I) -> Self
+    where
+        I: IntoIterator<Item = Encoding>,
+    {
+        self.candidates = candidates.into_iter().collect();
+        self
+    }
+
+    /// 设置无法解码字节的处理方式（默认 [`DecodePolicy::Lossy`]）
+    pub fn decode_policy(mut self, policy: DecodePolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// 设置 DM 服务器所在时区相对 UTC 的偏移（秒，东为正；默认 0）。
+    ///
+    /// sqllog 时间戳是服务器本地时间，[`Sqllog::timestamp_millis`] 等访问器据此换算为 UTC。
+    /// 例如 UTC+8 传入 `8 * 3600`。
+    ///
+    /// # Panics
+    ///
+    /// 偏移绝对值不小于 24 小时时 panic。
+    pub fn utc_offset(mut self, seconds: i32) -> Self {
+        assert!(
+            seconds.unsigned_abs() < 86_400,
+            "utc_offset must be within ±24h, got {seconds}s"
+        );
+        self.utc_offset = seconds;
+        self
+    }
+
+    /// 只把这些方括号标记识别为标签（默认识别任意不含空格、不超过 32 字节的标记）。
+    ///
+    /// 未列出的标记保留在 SQL 正文中，避免以 `[` 开头的真实 SQL 被误判为标签。
+    /// 未收录的标记需以 [`SqllogTag::Other`] 逐个列出（按名称匹配，不区分大小写）。
+    ///
+    /// ```rust
+    /// use dm_database_parser_sqllog::{LogParser, SqllogTag};
+    ///
+    /// let log = b"2025-08-12 10:57:09.548 (EP[0] sess:1 thrd:2 user:a trxid:3 stmt:4 appname:app) [T1] SELECT 1";
+    /// let parser = LogParser::builder()
+    ///     .recognized_tags([SqllogTag::Sel, SqllogTag::Ora])
+    ///     .open_bytes(log.as_slice());
+    /// let record = parser.iter().next().unwrap().unwrap();
+    /// assert_eq!(record.tag, None);
+    /// assert_eq!(record.body(), "[T1] SELECT 1");
+    /// ```
+    pub fn recognized_tags<I>(mut self, tags: I) -> Self
+    where
+        I: IntoIterator<Item = SqllogTag>,
+    {
+        self.tags = TagFilter::from_tags(tags);
+        self
+    }
+
+    /// 打开日志文件（内存映射）。
+    ///
+    /// 若文件是 gzip / zstd / xz 压缩归档（按魔数识别），会在启用对应 feature
+    /// （`gzip`、`zstd`、`xz`）时透明解压到内存；未启用时返回
+    /// [`ParseError::UnsupportedCompression`]。
+    pub fn open<P: AsRef<Path>>(self, path: P) -> Result<LogParser, ParseError> {
+        let path = path.as_ref();
         let file = File::open(path).map_err(|e| ParseError::IoError(e.to_string()))?;
         let mmap = unsafe { Mmap::map(&file).map_err(|e| ParseError::IoError(e.to_string()))? };
 
+        let compression = Compression::detect(&mmap);
+        if compression != Compression::None {
+            let decompressed = compression.decompress(&mmap)?;
+            let mut parser = self.open_bytes(decompressed);
+            parser.path = Some(path.to_path_buf());
+            return Ok(parser);
+        }
+
         // HOT-04: 告知 OS 以顺序模式预读 mmap 页面，减少 page fault 开销
         // Unix-only；Windows 上 advise() 方法不存在，cfg 门控跳过
         // 失败（如内核不支持）静默忽略，不影响正确性
         #[cfg(unix)]
         let _ = mmap.advise(Advice::Sequential);
 
-        // Detect encoding by sampling the first 64 KB and the last 4 KB.
-        // Sampling both ends catches the rare case where GB18030 content only
-        // appears after the initial UTF-8 section (e.g. late-joined non-ASCII
-        // usernames), while keeping the cost well below a full-file scan.
-        //
-        // Known limitation: the middle of large files (> ~68 KB) is not sampled.
-        // GB18030 multi-byte sequences that appear only in the middle of a very
-        // large log file may cause the file to be misclassified as UTF-8, leading
-        // to garbled output for those records. In practice DM log files either use
-        // GB18030 throughout or are entirely ASCII-safe UTF-8, so this edge case
-        // is unlikely. A full middle-window sample could be added if misclassification
-        // is observed in production.
-        let head_size = mmap.len().min(64 * 1024);
-        let tail_start = mmap.len().saturating_sub(4 * 1024).max(head_size);
-        let head_ok = simd_from_utf8(&mmap[..head_size]).is_ok();
-        let tail_ok = tail_start >= mmap.len() || simd_from_utf8(&mmap[tail_start..]).is_ok();
-        let encoding = if head_ok && tail_ok {
-            FileEncodingHint::Utf8
-        } else {
-            FileEncodingHint::Gb18030
+        let mut parser = self.build(Backing::Mmap(mmap));
+        parser.path = Some(path.to_path_buf());
+        Ok(parser)
+    }
+
+    /// 从内存中的字节缓冲区构建解析器，参见 [`LogParser::from_bytes`]
+    pub fn open_bytes<B>(self, bytes: B) -> LogParser
+    where
+        B: AsRef<[u8]> + Send + Sync + 'static,
+    {
+        self.build(Backing::Bytes(Box::new(bytes)))
+    }
+
+    /// 读取 `reader` 全部内容后构建解析器，参见 [`LogParser::from_reader`]
+    pub fn open_reader<R: Read>(self, mut reader: R) -> Result<LogParser, ParseError> {
+        let mut buf = Vec::new();
+        reader
+            .read_to_end(&mut buf)
+            .map_err(|e| ParseError::IoError(e.to_string()))?;
+        Ok(self.open_bytes(buf))
+    }
+
+    /// 无法预先扫描整个输入时（流式解析、跟踪模式、多文件集合中的流式读取）使用的逐记录选项。
+    ///
+    /// 自动探测退化为逐记录判定（UTF-8 或 GB18030，不使用候选编码）；强制 UTF-8 时逐段校验；
+    /// UTF-16 需整体转码，流式场景不支持，按逐记录判定处理。
+    pub(crate) fn streaming_options(&self) -> ParseOptions {
+        let encoding = match self.encoding {
+            Encoding::Utf8 => FileEncodingHint::Utf8Lossy,
+            Encoding::Gb18030 | Encoding::Gbk | Encoding::Gb2312 | Encoding::Big5 => {
+                self.encoding.resolve(&[], &[])
+            }
+            Encoding::AutoSampled
+            | Encoding::AutoFullScan
+            | Encoding::PerRecord
+            | Encoding::Utf16Le
+            | Encoding::Utf16Be => FileEncodingHint::PerRecord,
         };
+        ParseOptions {
+            encoding,
+            policy: self.policy,
+            utc_offset: self.utc_offset,
+            tags: self.tags.clone(),
+        }
+    }
+
+    fn build(self, data: Backing) -> LogParser {
+        // UTF-16 的记录边界无法按字节识别，整体转码为 UTF-8 后再解析
+        if let Some(utf8) = self.encoding.transcode_utf16(&data) {
+            return LogParser {
+                data: Arc::new(Backing::Bytes(Box::new(utf8))),
+                options: ParseOptions {
+                    encoding: FileEncodingHint::Utf8,
+                    policy: self.policy,
+                    utc_offset: self.utc_offset,
+                    tags: self.tags,
+                },
+                path: None,
+            };
+        }
+        let options = ParseOptions {
+            encoding: self.encoding.resolve(&data, &self.candidates),
+            policy: self.policy,
+            utc_offset: self.utc_offset,
+            tags: self.tags,
+        };
+        LogParser {
+            data: Arc::new(data),
+            options,
+            path: None,
+        }
+    }
+}
 
-        Ok(Self { mmap, encoding })
+impl LogParser {
+    /// 返回用于设置解析选项的构建器
+    pub fn builder() -> LogParserBuilder {
+        LogParserBuilder::new()
+    }
+
+    /// 以默认选项打开日志文件（内存映射），参见 [`LogParserBuilder::open`]。
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, ParseError> {
+        LogParserBuilder::new().open(path)
+    }
+
+    /// 从内存中的字节缓冲区构建解析器，无需落盘。
+    ///
+    /// 接受任何拥有所有权的字节容器（`Vec<u8>`、`Box<[u8]>`、`Arc<[u8]>`、
+    /// `&'static [u8]`、`bytes::Bytes` 等）。编码探测与 [`LogParser::from_path`] 相同。
+    ///
+    /// `LogParser` 不带生命周期参数，因此无法直接借用非 `'static` 的切片：
+    /// 调用方需要保留缓冲区时可先转为 `Arc<[u8]>` 再传入克隆（不复制数据），
+    /// 否则传入 `slice.to_vec()`。
+    pub fn from_bytes<B>(bytes: B) -> Self
+    where
+        B: AsRef<[u8]> + Send + Sync + 'static,
+    {
+        LogParserBuilder::new().open_bytes(bytes)
+    }
+
+    /// 从任意 `Read` 读取全部内容后构建解析器（如 HTTP 请求体、对象存储下载流）。
+    ///
+    /// 内容会被完整读入内存，以便 `iter()` / `par_iter()` 随机访问；
+    /// 需要有界内存的场景请使用 [`StreamingLogParser`](crate::StreamingLogParser)。
+    pub fn from_reader<R: Read>(reader: R) -> Result<Self, ParseError> {
+        LogParserBuilder::new().open_reader(reader)
+    }
+
+    /// 解析器持有的完整数据（已解压、UTF-16 已转码）。
+    ///
+    /// [`ColumnBatch::body_ranges`](crate::ColumnBatch::body_ranges) 等字节范围均相对于此切片。
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// 通过 [`from_path`](Self::from_path) / [`LogParserBuilder::open`] 打开时的文件路径；
+    /// 从内存或 `Read` 构建时为 `None`。
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
     }
 
     pub fn iter(&self) -> LogIterator<'_> {
-        LogIterator {
-            data: &self.mmap,
-            pos: 0,
-            encoding: self.encoding,
+        LogIterator::new(&self.data, 0, &self.options)
+    }
+
+    /// 从最新（文件末尾）的记录开始向前迭代，等价于 `self.iter().rev()`。
+    ///
+    /// 只解析实际取到的记录，适合"最近 N 条"一类查询。
+    pub fn iter_rev(&self) -> std::iter::Rev<LogIterator<'_>> {
+        self.iter().rev()
+    }
+
+    /// 从指定字节偏移开始迭代（用于断点续传）。
+    ///
+    /// `offset` 必须是记录边界：`0`、数据末尾，或紧跟在 `'\n'` 之后的时间戳行起点；
+    /// 否则返回 [`ParseError::InvalidOffset`]。
+    pub fn iter_from(&self, offset: usize) -> Result<LogIterator<'_>, ParseError> {
+        let data: &[u8] = &self.data;
+        let is_boundary = offset == 0
+            || offset == data.len()
+            || (offset < data.len()
+                && data[offset - 1] == b'\n'
+                && offset + 23 <= data.len()
+                && is_timestamp_start(&data[offset..offset + 23]));
+        if !is_boundary {
+            return Err(ParseError::InvalidOffset { offset });
         }
+        Ok(LogIterator::new(data, offset, &self.options))
+    }
+
+    /// 校验检查点属于本解析器打开的文件且文件未被替换或截断后，从检查点记录的偏移继续迭代。
+    ///
+    /// 校验规则见 [`Checkpoint::validate`]。
+    pub fn iter_from_checkpoint(
+        &self,
+        checkpoint: &Checkpoint,
+    ) -> Result<LogIterator<'_>, ParseError> {
+        checkpoint.validate(self)?;
+        self.iter_from(checkpoint.offset)
+    }
+
+    /// 迭代时间戳落在 `[start, end)` 内的记录。
+    ///
+    /// 边界格式为 `"YYYY-MM-DD HH:MM:SS.mmm"`，可省略尾部字段（如 `"2025-08-12 14:00"`），
+    /// 与 `ts` 一样按服务器本地时间比较。利用 DM 按时间顺序写日志的特点，先在字节偏移上
+    /// 二分查找起点，再顺序迭代到第一条不早于 `end` 的记录为止，无需扫描整个文件。
+    ///
+    /// 边界无法解析时返回 [`ParseError::InvalidFormat`]。
+    pub fn range(&self, start: &str, end: &str) -> Result<RangeIter<'_>, ParseError> {
+        self.range_with_tolerance(start, end, Duration::ZERO)
+    }
+
+    /// 同 [`range`](Self::range)，但允许记录时间戳在 `tolerance` 内乱序。
+    ///
+    /// 多个线程并发写日志时，时间戳可能略有倒退。查找从 `start - tolerance` 开始，
+    /// 直到遇到不早于 `end + tolerance` 的记录才停止，其间只产出 `[start, end)` 内的记录。
+    pub fn range_with_tolerance(
+        &self,
+        start: &str,
+        end: &str,
+        tolerance: Duration,
+    ) -> Result<RangeIter<'_>, ParseError> {
+        let parse = |bound: &str| {
+            parse_bound(bound).ok_or_else(|| ParseError::InvalidFormat {
+                raw: bound.to_string(),
+            })
+        };
+        let start = parse(start)?;
+        let end = parse(end)?;
+        let tolerance = tolerance.as_millis().min(i64::MAX as u128) as i64;
+
+        let from = seek_ts(&self.data, start.saturating_sub(tolerance));
+        Ok(RangeIter {
+            inner: LogIterator::new(&self.data, from, &self.options),
+            start,
+            end,
+            stop: end.saturating_add(tolerance),
+        })
     }
 
     /// 两阶段扫描第一阶段：构建记录起始字节偏移索引。
     /// 单线程扫描整个文件，返回的 `RecordIndex` 可直接用于并行处理阶段。
     pub fn index(&self) -> RecordIndex {
-        let data: &[u8] = &self.mmap;
+        let data: &[u8] = &self.data;
         let mut offsets: Vec<usize> = Vec::new();
 
         // 第 0 条记录：仅当文件首字节即是时间戳时才单独 push
@@ -124,7 +429,54 @@ impl LogParser {
             // 在首行就是时间戳时会返回同一个 next，无限循环
             pos = next.saturating_add(1);
         }
-        RecordIndex { offsets }
+
+        let utc_offset_ms = self.options.utc_offset as i64 * 1000;
+        let timestamps = offsets
+            .iter()
+            .map(|&o| parse_ts_millis(&data[o..o + 23]).map_or(i64::MIN, |ts| ts - utc_offset_ms))
+            .collect();
+        RecordIndex {
+            offsets,
+            timestamps,
+            data_len: data.len(),
+            // 在构建时记录文件状态，使之后 save() 时文件的变化能在 load() 时被发现
+            stamp: self.path.as_deref().and_then(|p| file_stamp(p).ok()),
+            mapped: matches!(*self.data, Backing::Mmap(_)),
+        }
+    }
+
+    /// 按序号随机访问第 `n` 条记录；`n` 越界时返回 `None`。
+    ///
+    /// `index` 必须由同一数据构建（或经 [`RecordIndex::load`] 校验）；
+    /// 索引超出数据范围时返回 [`ParseError::InvalidOffset`]。
+    pub fn get(&self, index: &RecordIndex, n: usize) -> Option<Result<Sqllog<'_>, ParseError>> {
+        let range = index.byte_range(n)?;
+        Some(self.iter_bytes(range.start, range.end).and_then(|mut it| {
+            it.next().unwrap_or(Err(ParseError::InvalidOffset {
+                offset: range.start,
+            }))
+        }))
+    }
+
+    /// 迭代序号范围 `range` 内的记录（如 `1000..1100`），越界部分截断。
+    ///
+    /// 适用于分页浏览：只解析该页记录，无需从头扫描。
+    pub fn iter_index<R: RangeBounds<usize>>(
+        &self,
+        index: &RecordIndex,
+        range: R,
+    ) -> Result<LogIterator<'_>, ParseError> {
+        let span = index.span(range);
+        self.iter_bytes(span.start, span.end)
+    }
+
+    /// 迭代 `[start, end)` 字节范围内的记录，`start` 须为记录边界
+    fn iter_bytes(&self, start: usize, end: usize) -> Result<LogIterator<'_>, ParseError> {
+        let data: &[u8] = &self.data;
+        if end > data.len() || start > end {
+            return Err(ParseError::InvalidOffset { offset: end });
+        }
+        Ok(span_iter(data, start, end, &self.options))
     }
 
     /// Returns a Rayon parallel iterator over all log records.
@@ -139,12 +491,21 @@ impl LogParser {
     ) -> impl rayon::iter::ParallelIterator<Item = Result<Sqllog<'_>, ParseError>> + '_ {
         use rayon::prelude::*;
 
-        const PAR_THRESHOLD: usize = 32 * 1024 * 1024;
+        let data: &[u8] = &self.data;
+        let options = &self.options;
 
-        let data: &[u8] = &self.mmap;
-        let encoding = self.encoding;
+        self.par_bounds()
+            .into_par_iter()
+            .flat_map_iter(move |(start, end)| span_iter(data, start, end, options))
+    }
 
-        let bounds: Vec<(usize, usize)> = if data.is_empty() {
+    /// [`par_iter`](Self::par_iter) 使用的分区：按 Rayon 线程数在记录边界处切分的
+    /// `[start, end)` 字节范围，配合 [`span`](Self::span) 迭代各分区
+    pub(crate) fn par_bounds(&self) -> Vec<(usize, usize)> {
+        const PAR_THRESHOLD: usize = 32 * 1024 * 1024;
+
+        let data: &[u8] = &self.data;
+        if data.is_empty() {
             Vec::new()
         } else if data.len() < PAR_THRESHOLD {
             vec![(0, data.len())]
@@ -161,22 +522,114 @@ impl LogParser {
             starts.push(data.len());
             starts.dedup();
             starts.windows(2).map(|w| (w[0], w[1])).collect()
-        };
+        }
+    }
 
-        bounds
-            .into_par_iter()
-            .flat_map_iter(move |(start, end)| LogIterator {
-                data: &data[start..end],
-                pos: 0,
-                encoding,
-            })
+    /// 迭代 `[start, end)` 字节范围内的记录，边界须来自 [`par_bounds`](Self::par_bounds)
+    pub(crate) fn span(&self, start: usize, end: usize) -> LogIterator<'_> {
+        span_iter(&self.data, start, end, &self.options)
+    }
+
+    /// 按文件顺序产出的并行分块迭代器，参见 [`ParChunks`]。
+    ///
+    /// 各块由 Rayon 并行解析，但按原始顺序逐块产出，且同时缓冲的块数有上限。
+    pub fn par_chunks(&self) -> ParChunks<'_> {
+        ParChunks::new(&self.data, &self.options)
+    }
+
+    /// 按列批量读取记录，每批最多 `batch_size` 条记录（解析失败的记录同样计入），参见 [`Batches`]。
+    pub fn batches(&self, batch_size: usize) -> Batches<'_> {
+        Batches::new(&self.data, self.iter(), batch_size)
+    }
+
+    /// 并行解析、按文件顺序产出全部记录，等价于展开 [`par_chunks`](Self::par_chunks)。
+    ///
+    /// 适用于事务拼接、有序导出等依赖记录顺序的场景。
+    pub fn par_iter_ordered(&self) -> impl Iterator<Item = Result<Sqllog<'_>, ParseError>> + '_ {
+        self.par_chunks().flat_map(|chunk| chunk.records)
     }
 }
 
+/// 迭代 `data` 中 `[start, end)` 字节范围内的记录，`start` 与 `end` 须为记录边界。
+///
+/// 迭代器覆盖整个 `data`（错误偏移即为绝对偏移），只是截止于 `end`；
+/// 与下一条记录之间的 `'\n'` 被去掉，使末条记录与完整迭代时的切分一致。
+pub(crate) fn span_iter<'a>(
+    data: &'a [u8],
+    start: usize,
+    end: usize,
+    options: &'a ParseOptions,
+) -> LogIterator<'a> {
+    let end = if end < data.len() && end > start && data[end - 1] == b'\n' {
+        end - 1
+    } else {
+        end
+    };
+    LogIterator::new(&data[..end], start, options)
+}
+
 pub struct LogIterator<'a> {
     data: &'a [u8],
     pos: usize,
-    encoding: FileEncodingHint,
+    options: &'a ParseOptions,
+    stats: DecodeStats,
+}
+
+impl<'a> LogIterator<'a> {
+    fn new(data: &'a [u8], pos: usize, options: &'a ParseOptions) -> Self {
+        Self {
+            data,
+            pos,
+            options,
+            stats: DecodeStats::default(),
+        }
+    }
+
+    /// 解析一条记录并更新解码统计；`record_start` 用于将编码错误偏移换算为绝对偏移
+    fn parse(
+        &mut self,
+        record_slice: &'a [u8],
+        is_multiline: bool,
+        record_start: usize,
+    ) -> Result<Sqllog<'a>, ParseError> {
+        match parse_record_with_hint(record_slice, is_multiline, self.options) {
+            Ok(record) => {
+                self.stats.record(record.encoding);
+                if record.lossy {
+                    self.stats.lossy += 1;
+                }
+                Ok(record)
+            }
+            Err(ParseError::Encoding {
+                encoding,
+                offset,
+                raw,
+            }) => {
+                self.stats.lossy += 1;
+                Err(ParseError::Encoding {
+                    encoding,
+                    offset: record_start + offset,
+                    raw,
+                })
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// 截至目前已产出记录的解码统计
+    pub fn decode_stats(&self) -> DecodeStats {
+        self.stats
+    }
+}
+
+impl LogIterator<'_> {
+    /// 下一条待产出记录的字节偏移（相对于 `LogParser` 数据起点）。
+    ///
+    /// 保存该值后可通过 [`LogParser::iter_from`] 从同一位置继续。
+    /// 仅反映正向进度，`next_back()` 不改变该值。
+    pub fn position(&self) -> usize {
+        self.pos
+    }
 }
 
 impl<'a> Iterator for LogIterator<'a> {
@@ -223,6 +676,7 @@ impl<'a> Iterator for LogIterator<'a> {
             };
 
             let record_slice = &data[..record_end];
+            let record_start = self.pos;
             self.pos += next_start;
 
             // Trim trailing CR if present
@@ -238,18 +692,135 @@ impl<'a> Iterator for LogIterator<'a> {
                 continue;
             }
 
-            return Some(parse_record_with_hint(
-                record_slice,
-                is_multiline,
-                self.encoding,
-            ));
+            return Some(self.parse(record_slice, is_multiline, record_start));
         }
     }
 }
 
+impl<'a> DoubleEndedIterator for LogIterator<'a> {
+    /// 从末尾向前产出记录：用 `memrchr` 逐行回溯，直到找到前一行以 `'\n'` 结尾、
+    /// 本行以时间戳开头的记录起点，因此多行 SQL 中的嵌入换行会被正确跳过。
+    fn next_back(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.pos >= self.data.len() {
+                return None;
+            }
+
+            let data = &self.data[self.pos..];
+            let mut search_end = data.len();
+            let start = loop {
+                match memrchr(b'\n', &data[..search_end]) {
+                    Some(nl) => {
+                        let ts_start = nl + 1;
+                        if ts_start + 23 <= data.len()
+                            && is_timestamp_start(&data[ts_start..ts_start + 23])
+                        {
+                            break ts_start;
+                        }
+                        search_end = nl;
+                    }
+                    None => break 0,
+                }
+            };
+
+            let record_slice = &data[start..];
+            let record_start = self.pos + start;
+            // 剩余数据截止到该记录之前的 '\n'（不含），与正向迭代的切分一致
+            self.data = &self.data[..(self.pos + start).saturating_sub(1).max(self.pos)];
+
+            let record_slice = if record_slice.ends_with(b"\r") {
+                &record_slice[..record_slice.len() - 1]
+            } else {
+                record_slice
+            };
+            if record_slice.is_empty() {
+                continue;
+            }
+
+            let is_multiline = memchr(b'\n', record_slice).is_some();
+            return Some(self.parse(record_slice, is_multiline, record_start));
+        }
+    }
+}
+
+/// [`LogParser::range`] 返回的迭代器，产出时间戳在 `[start, end)` 内的记录。
+///
+/// 范围内的解析错误照常产出。
+pub struct RangeIter<'a> {
+    inner: LogIterator<'a>,
+    start: i64,
+    end: i64,
+    /// 遇到不早于该时间的记录即停止（`end + tolerance`）
+    stop: i64,
+}
+
+impl<'a> Iterator for RangeIter<'a> {
+    type Item = Result<Sqllog<'a>, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let record = match self.inner.next()? {
+                Ok(record) => record,
+                Err(e) => return Some(Err(e)),
+            };
+            // 时间戳字段越界的记录无法定位，跳过
+            let Some(ts) = parse_ts_millis(record.ts.as_bytes()) else {
+                continue;
+            };
+            if ts >= self.stop {
+                self.inner.pos = self.inner.data.len();
+                return None;
+            }
+            if ts >= self.start && ts < self.end {
+                return Some(Ok(record));
+            }
+        }
+    }
+}
+
+/// 二分查找第一条时间戳不早于 `target` 的记录起始偏移（假设记录大致按时间排序）。
+///
+/// 二分只维护"`lo` 之前的记录均早于 `target`"这一不变量；区间缩小到
+/// `SCAN_WINDOW` 以内后顺序扫描。
+fn seek_ts(data: &[u8], target: i64) -> usize {
+    const SCAN_WINDOW: usize = 64 * 1024;
+
+    let ts_at = |pos: usize| -> Option<i64> {
+        if pos + 23 <= data.len() && is_timestamp_start(&data[pos..pos + 23]) {
+            parse_ts_millis(&data[pos..pos + 23])
+        } else {
+            None
+        }
+    };
+
+    let mut lo = 0usize;
+    let mut hi = data.len();
+    while hi - lo > SCAN_WINDOW {
+        let mid = lo + (hi - lo) / 2;
+        let start = find_next_record_start(data, mid);
+        if start >= hi {
+            hi = mid;
+            continue;
+        }
+        match ts_at(start) {
+            Some(ts) if ts >= target => hi = start,
+            _ => lo = start,
+        }
+    }
+
+    let mut pos = lo;
+    while pos < data.len() {
+        if ts_at(pos).is_some_and(|ts| ts >= target) {
+            return pos;
+        }
+        pos = find_next_record_start(data, pos);
+    }
+    data.len()
+}
+
 /// Find the position of the next record start at or after `from`.
 /// A record start is a line beginning with a timestamp pattern.
-fn find_next_record_start(data: &[u8], from: usize) -> usize {
+pub(crate) fn find_next_record_start(data: &[u8], from: usize) -> usize {
     let mut pos = from;
     // Skip to start of next line
     if let Some(nl) = memchr(b'\n', &data[pos..]) {
@@ -277,14 +848,30 @@ pub fn parse_record<'a>(record_bytes: &'a [u8]) -> Result<Sqllog<'a>, ParseError
     // rather than hardcoding true, which caused a redundant memchr scan for
     // single-line records and was semantically misleading.
     let is_multiline = memchr(b'\n', record_bytes).is_some();
-    parse_record_with_hint(record_bytes, is_multiline, FileEncodingHint::Auto)
+    parse_record_with_hint(record_bytes, is_multiline, &ParseOptions::default())
 }
 
-fn parse_record_with_hint<'a>(
+/// 解析单条记录。
+///
+/// `DecodePolicy::Strict` 下返回的 `ParseError::Encoding` 偏移相对于 `record_bytes` 起点，
+/// 由调用方（迭代器）换算为绝对偏移。
+pub(crate) fn parse_record_with_hint<'a>(
     record_bytes: &'a [u8],
     is_multiline: bool,
-    encoding_hint: FileEncodingHint,
+    options: &ParseOptions,
 ) -> Result<Sqllog<'a>, ParseError> {
+    // PerRecord: validate the whole record once and decode it as a single unit.
+    let encoding_hint = match options.encoding {
+        FileEncodingHint::PerRecord => {
+            if simd_from_utf8(record_bytes).is_ok() {
+                FileEncodingHint::Utf8
+            } else {
+                FileEncodingHint::Gb18030
+            }
+        }
+        other => other,
+    };
+
     // Find end of first line
     let (first_line, _rest) = if is_multiline {
         match memchr(b'\n', record_bytes) {
@@ -347,8 +934,9 @@ fn parse_record_with_hint<'a>(
     // Lazy parsing: store raw bytes as a Cow<'a, str>.
     // For Utf8 / Auto-UTF8 encoding: meta_bytes is a sub-slice of the memory-mapped buffer
     // (raw UTF-8 bytes) that lives for 'a — borrowing is sound.
-    // For Gb18030 / Auto-GB18030 encoding: GB18030.decode() produces a new owned String, so
+    // For legacy (GB18030 / GBK / Big5) / Auto-GB18030 encoding: decode_legacy() produces a new owned String, so
     // meta_raw becomes Cow::Owned; the 'a lifetime is NOT extended to that allocation.
+    let mut lossy = false;
     let meta_raw = match encoding_hint {
         FileEncodingHint::Utf8 => {
             // File already validated as UTF-8 during `from_path`; skip per-slice re-validation.
@@ -356,22 +944,41 @@ fn parse_record_with_hint<'a>(
             // No lifetime extension via from_raw_parts needed — meta_bytes already carries 'a.
             unsafe { Cow::Borrowed(std::str::from_utf8_unchecked(meta_bytes)) }
         }
-        FileEncodingHint::Gb18030 => match GB18030.decode(meta_bytes, DecoderTrap::Strict) {
-            Ok(s) => Cow::Owned(s),
-            Err(_) => Cow::Owned(String::from_utf8_lossy(meta_bytes).into_owned()),
-        },
-        FileEncodingHint::Auto => match simd_from_utf8(meta_bytes) {
-            Ok(_) => {
-                // SAFETY: meta_bytes is a sub-slice of record_bytes which lives for 'a;
-                // simd_from_utf8 confirmed it is valid UTF-8.
-                unsafe { Cow::Borrowed(std::str::from_utf8_unchecked(meta_bytes)) }
+        FileEncodingHint::Gb18030 | FileEncodingHint::Gbk | FileEncodingHint::Big5 => {
+            Cow::Owned(decode_or_flag(encoding_hint, meta_bytes, &mut lossy))
+        }
+        FileEncodingHint::Auto | FileEncodingHint::PerRecord | FileEncodingHint::Utf8Lossy => {
+            match simd_from_utf8(meta_bytes) {
+                Ok(_) => {
+                    // SAFETY: meta_bytes is a sub-slice of record_bytes which lives for 'a;
+                    // simd_from_utf8 confirmed it is valid UTF-8.
+                    unsafe { Cow::Borrowed(std::str::from_utf8_unchecked(meta_bytes)) }
+                }
+                // 强制 UTF-8：不回退到 GB18030，与正文一致地有损替换
+                Err(_) if encoding_hint == FileEncodingHint::Utf8Lossy => {
+                    lossy = true;
+                    Cow::Owned(String::from_utf8_lossy(meta_bytes).into_owned())
+                }
+                Err(_) => Cow::Owned(decode_or_flag(
+                    FileEncodingHint::Gb18030,
+                    meta_bytes,
+                    &mut lossy,
+                )),
             }
-            Err(_) => match GB18030.decode(meta_bytes, DecoderTrap::Strict) {
-                Ok(s) => Cow::Owned(s),
-                Err(_) => Cow::Owned(String::from_utf8_lossy(meta_bytes).into_owned()),
-            },
-        },
+        }
     };
+    if lossy && options.policy == DecodePolicy::Strict {
+        let meta_hint = match encoding_hint {
+            FileEncodingHint::Auto | FileEncodingHint::PerRecord => FileEncodingHint::Gb18030,
+            other => other,
+        };
+        let at = invalid_offset(meta_hint, meta_bytes).unwrap_or(0);
+        return Err(make_encoding_error(
+            first_line,
+            meta_hint,
+            meta_start + 1 + at,
+        ));
+    }
 
     // 3. Body & 4. Indicators
     let body_start_in_first_line = meta_end + 1;
@@ -397,7 +1004,7 @@ fn parse_record_with_hint<'a>(
         {
             let inner = &s[1..end_idx];
             // Accept token without spaces and reasonable length
-            if !inner.contains(&b' ') && inner.len() <= 32 {
+            if !inner.contains(&b' ') && inner.len() <= 32 && options.tags.accepts(inner) {
                 tag = match encoding_hint {
                     FileEncodingHint::Utf8 => {
                         // File already validated as UTF-8; skip re-validation.
@@ -412,18 +1019,26 @@ fn parse_record_with_hint<'a>(
                             Cow::Borrowed(std::str::from_utf8_unchecked(inner))
                         }),
                         Err(_) => match encoding_hint {
-                            FileEncodingHint::Gb18030 => {
-                                match GB18030.decode(inner, DecoderTrap::Strict) {
-                                    Ok(s) => Some(Cow::Owned(s)),
-                                    Err(_) => Some(Cow::Owned(
-                                        String::from_utf8_lossy(inner).into_owned(),
-                                    )),
-                                }
+                            FileEncodingHint::Gb18030
+                            | FileEncodingHint::Gbk
+                            | FileEncodingHint::Big5 => {
+                                Some(Cow::Owned(decode_or_flag(encoding_hint, inner, &mut lossy)))
+                            }
+                            _ => {
+                                lossy = true;
+                                Some(Cow::Owned(String::from_utf8_lossy(inner).into_owned()))
                             }
-                            _ => Some(Cow::Owned(String::from_utf8_lossy(inner).into_owned())),
                         },
                     },
                 };
+                if lossy && options.policy == DecodePolicy::Strict {
+                    let at = invalid_offset(encoding_hint, inner).unwrap_or(0);
+                    return Err(make_encoding_error(
+                        first_line,
+                        encoding_hint,
+                        content_start + 1 + at,
+                    ));
+                }
                 // Move past the closing ']' and any following ASCII whitespace
                 s = &s[end_idx + 1..];
                 let mut skip = 0usize;
@@ -435,9 +1050,25 @@ fn parse_record_with_hint<'a>(
         }
         s
     } else {
-        &[] as &[u8]
+        // 无正文时仍借用 record_bytes 末尾的空切片，保证 content_raw 总是指向原始数据内部
+        &record_bytes[record_bytes.len()..]
     };
 
+    // Report / Strict: 额外校验延迟解码的正文，使有损解码在解析时即可发现
+    if options.policy != DecodePolicy::Lossy
+        && let Some(at) = invalid_offset(encoding_hint, content_slice)
+    {
+        if options.policy == DecodePolicy::Strict {
+            let content_offset = record_bytes.len() - content_slice.len();
+            return Err(make_encoding_error(
+                first_line,
+                encoding_hint,
+                content_offset + at,
+            ));
+        }
+        lossy = true;
+    }
+
     let content_raw = Cow::Borrowed(content_slice);
 
     Ok(Sqllog {
@@ -446,6 +1077,8 @@ fn parse_record_with_hint<'a>(
         content_raw,
         tag,
         encoding: encoding_hint,
+        lossy,
+        utc_offset: options.utc_offset,
     })
 }
 
@@ -459,7 +1092,7 @@ const HI_EXPECTED: u64 = 0x00003A0000200000; // LE: ' '=0x20,':'=0x3A
 /// 检查 bytes[0..23] 是否符合时间戳格式 "20YY-MM-DD HH:MM:SS.mmm"。
 /// 调用前需确保 bytes.len() >= 23（由调用方做长度检查）。
 #[inline(always)]
-fn is_timestamp_start(bytes: &[u8]) -> bool {
+pub(crate) fn is_timestamp_start(bytes: &[u8]) -> bool {
     debug_assert!(bytes.len() >= 23);
     let lo = u64::from_le_bytes(bytes[0..8].try_into().unwrap());
     let hi = u64::from_le_bytes(bytes[8..16].try_into().unwrap());
@@ -477,3 +1110,20 @@ fn make_invalid_format_error(raw_bytes: &[u8]) -> ParseError {
         raw: String::from_utf8_lossy(raw_bytes).to_string(),
     }
 }
+
+fn make_encoding_error(first_line: &[u8], hint: FileEncodingHint, offset: usize) -> ParseError {
+    ParseError::Encoding {
+        encoding: encoding_name(hint).to_string(),
+        offset,
+        raw: String::from_utf8_lossy(first_line).to_string(),
+    }
+}
+
+/// 按遗留编码解码；存在非法字节时有损回退并置位 `lossy`
+#[inline]
+fn decode_or_flag(hint: FileEncodingHint, bytes: &[u8], lossy: &mut bool) -> String {
+    try_decode_legacy(hint, bytes).unwrap_or_else(|| {
+        *lossy = true;
+        String::from_utf8_lossy(bytes).into_owned()
+    })
+}
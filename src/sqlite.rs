@@ -0,0 +1,269 @@
+//! SQLite 导出（需启用 `sqlite` feature）
+//!
+//! 把记录展开为一张普通表，便于用 `sqlite3` 命令行或任意 SQLite 客户端做即席查询。
+
+use std::borrow::Cow;
+use std::path::Path;
+use std::sync::mpsc::sync_channel;
+use std::thread;
+
+use rayon::prelude::*;
+use rusqlite::{CachedStatement, Connection, params};
+
+use crate::error::ParseError;
+use crate::export::ExportStats;
+use crate::parser::LogParser;
+use crate::sqllog::{MetaParts, PerformanceMetrics, Sqllog};
+
+/// 默认每个事务插入的行数
+const DEFAULT_BATCH_ROWS: usize = 50_000;
+
+/// 解析线程与写入线程之间最多排队的行数
+const CHANNEL_ROWS: usize = 16 * 1024;
+
+/// 建索引的列及索引名后缀
+const INDEXED_COLUMNS: [&str; 4] = ["ts", "username", "trxid", "exectime"];
+
+fn sqlite_error(e: rusqlite::Error) -> ParseError {
+    ParseError::ExportError(e.to_string())
+}
+
+/// 双引号包裹的 SQL 标识符
+fn quote_ident(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// 一行待插入的数据；并行导出时在 Rayon 线程中构建，字符串仍借用原记录
+struct Row<'a> {
+    ts: Cow<'a, str>,
+    meta: MetaParts<'a>,
+    tag: Option<Cow<'a, str>>,
+    sql: Cow<'a, str>,
+    indicators: Option<PerformanceMetrics<'static>>,
+}
+
+impl<'a> Row<'a> {
+    fn new(record: &Sqllog<'a>) -> Self {
+        let (sql, indicators) = record.sql_and_indicators();
+        Self {
+            ts: record.ts.clone(),
+            meta: record.parse_meta(),
+            tag: record.tag.clone(),
+            sql,
+            indicators,
+        }
+    }
+
+    fn insert(&self, stmt: &mut CachedStatement<'_>) -> rusqlite::Result<()> {
+        let meta = &self.meta;
+        let pm = self.indicators.as_ref();
+        stmt.execute(params![
+            self.ts.as_ref(),
+            meta.ep,
+            meta.sess_id.as_ref(),
+            meta.thrd_id.as_ref(),
+            meta.username.as_ref(),
+            meta.trxid.as_ref(),
+            meta.statement.as_ref(),
+            meta.appname.as_ref(),
+            (!meta.client_ip.is_empty()).then_some(meta.client_ip.as_ref()),
+            self.tag.as_deref(),
+            self.sql.as_ref(),
+            pm.map(|pm| pm.exectime as f64),
+            pm.map(|pm| pm.rowcount),
+            pm.map(|pm| pm.exec_id),
+        ])?;
+        Ok(())
+    }
+}
+
+/// 把记录批量写入 SQLite 表
+///
+/// 表在首次写入时按以下结构创建（已存在则直接追加），列名与 Arrow 导出一致：
+///
+/// | 列 | 类型 | 说明 |
+/// |----|------|------|
+/// | `ts` | `TEXT` | 服务器本地时间，字符串顺序即时间顺序 |
+/// | `ep` | `INTEGER` | |
+/// | `sess_id` / `thrd_id` / `username` / `trxid` / `statement` / `appname` | `TEXT` | |
+/// | `client_ip` / `tag` | `TEXT` | 缺失时为 `NULL` |
+/// | `sql` | `TEXT` | ORA 记录已去除 `": "` 前缀 |
+/// | `exectime` / `rowcount` / `exec_id` | `REAL` / `INTEGER` / `INTEGER` | 无性能指标时为 `NULL` |
+///
+/// 每 [`batch_size`](Self::batch_size) 行提交一次事务，复用同一条预编译 `INSERT`。
+///
+/// ```rust,no_run
+/// use dm_database_parser_sqllog::{LogParser, SqliteSink};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let parser = LogParser::from_path("sqllog.txt")?;
+/// let mut sink = SqliteSink::open("sqllog.db")?.with_indexes(true);
+/// let stats = sink.par_insert(&parser)?;
+/// sink.finish()?;
+/// println!("{} 行，{} 条解析失败", stats.rows, stats.errors);
+/// # Ok(())
+/// # }
+/// ```
+pub struct SqliteSink {
+    conn: Connection,
+    table: String,
+    batch_size: usize,
+    indexes: bool,
+    created: bool,
+}
+
+impl SqliteSink {
+    /// 打开（或创建）数据库文件
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, ParseError> {
+        Ok(Self::from_connection(
+            Connection::open(path).map_err(sqlite_error)?,
+        ))
+    }
+
+    /// 使用已有连接（如内存数据库或已设置 PRAGMA 的连接）
+    pub fn from_connection(conn: Connection) -> Self {
+        Self {
+            conn,
+            table: "sqllog".to_string(),
+            batch_size: DEFAULT_BATCH_ROWS,
+            indexes: false,
+            created: false,
+        }
+    }
+
+    /// 目标表名（默认 `sqllog`）
+    pub fn table(mut self, name: &str) -> Self {
+        self.table = name.to_string();
+        self.created = false;
+        self
+    }
+
+    /// 每个事务插入的行数（默认 50 000）
+    pub fn batch_size(mut self, rows: usize) -> Self {
+        self.batch_size = rows.max(1);
+        self
+    }
+
+    /// [`finish`](Self::finish) 时在 `ts`、`username`、`trxid`、`exectime` 上建索引（默认不建）
+    ///
+    /// 索引在全部数据写入后一次性构建，比边插入边维护更快。
+    pub fn with_indexes(mut self, yes: bool) -> Self {
+        self.indexes = yes;
+        self
+    }
+
+    /// 底层连接，可用于查询或执行自定义语句
+    pub fn connection(&self) -> &Connection {
+        &self.conn
+    }
+
+    fn create_table(&mut self) -> Result<(), ParseError> {
+        if self.created {
+            return Ok(());
+        }
+        let sql = format!(
+            "CREATE TABLE IF NOT EXISTS {} (
+                ts TEXT NOT NULL,
+                ep INTEGER NOT NULL,
+                sess_id TEXT NOT NULL,
+                thrd_id TEXT NOT NULL,
+                username TEXT NOT NULL,
+                trxid TEXT NOT NULL,
+                statement TEXT NOT NULL,
+                appname TEXT NOT NULL,
+                client_ip TEXT,
+                tag TEXT,
+                sql TEXT NOT NULL,
+                exectime REAL,
+                rowcount INTEGER,
+                exec_id INTEGER
+            )",
+            quote_ident(&self.table)
+        );
+        self.conn.execute_batch(&sql).map_err(sqlite_error)?;
+        self.created = true;
+        Ok(())
+    }
+
+    /// 按批开启事务插入；`None` 表示解析失败的记录，只计数
+    fn insert_rows<'a, I>(&mut self, rows: I) -> Result<ExportStats, ParseError>
+    where
+        I: Iterator<Item = Option<Row<'a>>>,
+    {
+        self.create_table()?;
+        let insert = format!(
+            "INSERT INTO {} (ts, ep, sess_id, thrd_id, username, trxid, statement, appname,
+                client_ip, tag, sql, exectime, rowcount, exec_id)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+            quote_ident(&self.table)
+        );
+        let mut stats = ExportStats::default();
+        let mut rows = rows.peekable();
+        while rows.peek().is_some() {
+            let tx = self.conn.transaction().map_err(sqlite_error)?;
+            {
+                let mut stmt = tx.prepare_cached(&insert).map_err(sqlite_error)?;
+                for row in rows.by_ref().take(self.batch_size) {
+                    match row {
+                        Some(row) => {
+                            row.insert(&mut stmt).map_err(sqlite_error)?;
+                            stats.rows += 1;
+                        }
+                        None => stats.errors += 1,
+                    }
+                }
+            }
+            tx.commit().map_err(sqlite_error)?;
+        }
+        Ok(stats)
+    }
+
+    /// 依次插入全部记录，返回插入的行数
+    pub fn insert_all<'a, I>(&mut self, records: I) -> Result<usize, ParseError>
+    where
+        I: IntoIterator<Item = Sqllog<'a>>,
+    {
+        let rows = records.into_iter().map(|record| Some(Row::new(&record)));
+        Ok(self.insert_rows(rows)?.rows)
+    }
+
+    /// 用 [`LogParser::par_iter`] 并行解析并展开字段，由当前线程按批写入；
+    /// 解析失败的记录被跳过并计数
+    ///
+    /// 行的插入顺序不确定，需要时按 `ts` 排序查询。
+    pub fn par_insert(&mut self, parser: &LogParser) -> Result<ExportStats, ParseError> {
+        thread::scope(|s| {
+            let (tx, rx) = sync_channel(CHANNEL_ROWS);
+            s.spawn(move || {
+                // 写入失败时接收端被丢弃，send 返回错误以提前结束并行解析
+                let _ = parser.par_iter().try_for_each_with(tx, |tx, record| {
+                    tx.send(record.ok().map(|record| Row::new(&record)))
+                        .map_err(drop)
+                });
+            });
+            self.insert_rows(rx.into_iter())
+        })
+    }
+
+    /// 在 `ts`、`username`、`trxid`、`exectime` 上建索引（已存在则跳过）
+    pub fn create_indexes(&mut self) -> Result<(), ParseError> {
+        self.create_table()?;
+        for column in INDEXED_COLUMNS {
+            let sql = format!(
+                "CREATE INDEX IF NOT EXISTS {} ON {} ({column})",
+                quote_ident(&format!("{}_{column}", self.table)),
+                quote_ident(&self.table)
+            );
+            self.conn.execute_batch(&sql).map_err(sqlite_error)?;
+        }
+        Ok(())
+    }
+
+    /// 按配置建索引并返回底层连接
+    pub fn finish(mut self) -> Result<Connection, ParseError> {
+        if self.indexes {
+            self.create_indexes()?;
+        }
+        Ok(self.conn)
+    }
+}
@@ -1,15 +1,15 @@
 use atoi::atoi;
-use encoding::DecoderTrap;
-use encoding::Encoding;
-use encoding::all::GB18030;
 use memchr::memchr;
 use memchr::memmem::Finder;
 use memchr::memrchr;
 use simdutf8::basic::from_utf8 as simd_from_utf8;
 use std::borrow::Cow;
+use std::ops::Range;
 use std::sync::LazyLock;
 
+use crate::charset::{Encoding, decode_legacy};
 use crate::parser::FileEncodingHint;
+use crate::timestamp;
 
 /// Pre-built SIMD finders for performance indicators — avoids per-call initialization.
 static FINDER_EXECTIME: LazyLock<Finder<'static>> = LazyLock::new(|| Finder::new(b"EXECTIME:"));
@@ -24,22 +24,40 @@ const INDICATORS_WINDOW: usize = 256;
 /// SQL 日志记录
 ///
 /// 表示一条完整的 SQL 日志记录，包含时间戳、元数据、SQL 语句体和可选的性能指标。
+///
+/// 启用 `serde` feature 后可序列化，保留原始字节与解码信息以便无损往返；
+/// 需要展开后的字段时使用 [`view`](Self::view)。
+///
+/// 反序列化时会校验解码信息：声明为 UTF-8 但内容不是合法 UTF-8 的记录改为有损解码，
+/// 超出 ±24 小时的时区偏移视为错误。
 #[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Sqllog<'a> {
     /// 时间戳，格式为 "YYYY-MM-DD HH:MM:SS.mmm"
+    #[cfg_attr(feature = "serde", serde(borrow))]
     pub ts: Cow<'a, str>,
 
     /// 原始元数据字节（延迟解析）
+    #[cfg_attr(feature = "serde", serde(borrow))]
     pub meta_raw: Cow<'a, str>,
 
     /// 原始内容（包含 Body 和 Indicators），延迟分割和解析
+    #[cfg_attr(feature = "serde", serde(borrow, with = "content_bytes"))]
     pub content_raw: Cow<'a, [u8]>,
 
     /// 提取出的方括号标签（例如 [SEL]、[ORA]），若无则为 None
+    #[cfg_attr(feature = "serde", serde(borrow))]
     pub tag: Option<Cow<'a, str>>,
 
     /// 文件级编码 hint（由 parser 探测），用于正确解码 content
+    #[cfg_attr(feature = "serde", serde(with = "serde_impl::hint"))]
     pub(crate) encoding: FileEncodingHint,
+
+    /// 解析时发现无法解码的字节并做了有损替换
+    pub(crate) lossy: bool,
+
+    /// 服务器时区相对 UTC 的偏移（秒），由解析器设置
+    pub(crate) utc_offset: i32,
 }
 
 impl<'a> Sqllog<'a> {
@@ -90,29 +108,87 @@ impl<'a> Sqllog<'a> {
     ///
     /// 返回包含 EXECTIME、ROWCOUNT、EXEC_ID 和 SQL 语句的 [`PerformanceMetrics`]。
     ///
-    /// 当 tag 为 `"ORA"` 时，SQL 语句开头可能带有 `": "`，本方法会自动去除。
+    /// 当 tag 为 `"ORA"`（不区分大小写）时，SQL 语句开头可能带有 `": "`，本方法会自动去除。
     ///
     /// # 实现说明
     /// 仅调用一次 `find_indicators_split()`，body 解码与 indicators 解析均在同一
     /// 次遍历中完成，`Cow::Borrowed` 路径全程零分配。
     #[inline(always)]
     pub fn parse_performance_metrics(&self) -> PerformanceMetrics<'a> {
-        let split = self.find_indicators_split();
-        let is_borrowed = matches!(&self.content_raw, Cow::Borrowed(_));
+        let (sql, indicators) = self.sql_and_indicators();
+        let mut pm = indicators.unwrap_or_default();
+        pm.sql = sql;
+        pm
+    }
 
-        // SAFETY: 子切片与 content_raw 共享 'a 生命周期
-        let sql_raw =
-            unsafe { decode_content_bytes(&self.content_raw[..split], is_borrowed, self.encoding) };
+    /// 该记录实际采用的解码方式
+    ///
+    /// 返回 `Some(Encoding::Utf8)`、`Gb18030`、`Gbk` 或 `Big5`；由 [`parse_record`](crate::parse_record)
+    /// 解析时返回 `None`。
+    pub fn record_encoding(&self) -> Option<Encoding> {
+        match self.encoding {
+            FileEncodingHint::Utf8 | FileEncodingHint::Utf8Lossy => Some(Encoding::Utf8),
+            FileEncodingHint::Gb18030 => Some(Encoding::Gb18030),
+            FileEncodingHint::Gbk => Some(Encoding::Gbk),
+            FileEncodingHint::Big5 => Some(Encoding::Big5),
+            FileEncodingHint::Auto | FileEncodingHint::PerRecord => None,
+        }
+    }
 
-        let sql = if self.tag.as_deref() == Some("ORA") {
-            strip_ora_prefix(sql_raw)
-        } else {
-            sql_raw
-        };
+    /// 记录时间对应的 Unix 毫秒数（UTC，零分配）
+    ///
+    /// `ts` 按服务器本地时间解释，减去 [`utc_offset`](Self::utc_offset) 换算为 UTC。
+    /// 时间戳字段越界（如 13 月）时返回 `None`。
+    pub fn timestamp_millis(&self) -> Option<i64> {
+        let local = timestamp::parse_ts_millis(self.ts.as_bytes())?;
+        Some(local - self.utc_offset as i64 * 1000)
+    }
 
-        let mut pm = parse_indicators_from_bytes(&self.content_raw[split..]).unwrap_or_default();
-        pm.sql = sql;
-        pm
+    /// 服务器时区相对 UTC 的偏移（秒，东为正）
+    ///
+    /// 由 [`LogParserBuilder::utc_offset`](crate::LogParserBuilder::utc_offset) 设置，默认 0。
+    pub fn utc_offset(&self) -> i32 {
+        self.utc_offset
+    }
+
+    /// 带服务器时区偏移的 `chrono` 时间（需启用 `chrono` feature）
+    #[cfg(feature = "chrono")]
+    pub fn timestamp(&self) -> Option<chrono::DateTime<chrono::FixedOffset>> {
+        let offset = chrono::FixedOffset::east_opt(self.utc_offset)?;
+        let utc = chrono::DateTime::from_timestamp_millis(self.timestamp_millis()?)?;
+        Some(utc.with_timezone(&offset))
+    }
+
+    /// 带服务器时区偏移的 `time` 时间（需启用 `time` feature）
+    #[cfg(feature = "time")]
+    pub fn offset_date_time(&self) -> Option<time::OffsetDateTime> {
+        let offset = time::UtcOffset::from_whole_seconds(self.utc_offset).ok()?;
+        let nanos = self.timestamp_millis()? as i128 * 1_000_000;
+        let utc = time::OffsetDateTime::from_unix_timestamp_nanos(nanos).ok()?;
+        Some(utc.to_offset(offset))
+    }
+
+    /// 该记录是否包含无法按所选编码解码、被替换为 U+FFFD 的字节
+    ///
+    /// 默认的 [`DecodePolicy::Lossy`](crate::DecodePolicy::Lossy) 只检查 meta 与 tag；
+    /// 设置为 `Report` 时同时检查 SQL 正文。
+    pub fn is_lossy(&self) -> bool {
+        self.lossy
+    }
+
+    /// 转换为不借用原始缓冲区的 `Sqllog<'static>`（所有 `Cow` 字段转为 Owned）
+    ///
+    /// 适用于需要在缓冲区复用或释放后继续持有记录的场景（如流式解析）。
+    pub fn into_owned(self) -> Sqllog<'static> {
+        Sqllog {
+            ts: Cow::Owned(self.ts.into_owned()),
+            meta_raw: Cow::Owned(self.meta_raw.into_owned()),
+            content_raw: Cow::Owned(self.content_raw.into_owned()),
+            tag: self.tag.map(|t| Cow::Owned(t.into_owned())),
+            encoding: self.encoding,
+            lossy: self.lossy,
+            utc_offset: self.utc_offset,
+        }
     }
 
     /// 解析元数据
@@ -216,6 +292,55 @@ impl<'a> Sqllog<'a> {
         meta
     }
 
+    // ── Crate-internal helpers ────────────────────────────────────────────────
+
+    /// SQL 语句（ORA 记录已去除 `": "` 前缀）与性能指标；无指标时后者为 `None`。
+    ///
+    /// 仅调用一次 `find_indicators_split()`，供 [`parse_performance_metrics`](Self::parse_performance_metrics)
+    /// 与需要区分"无指标"的导出器共用。
+    #[inline(always)]
+    pub(crate) fn sql_and_indicators(&self) -> (Cow<'a, str>, Option<PerformanceMetrics<'static>>) {
+        let split = self.find_indicators_split();
+        let is_borrowed = matches!(&self.content_raw, Cow::Borrowed(_));
+
+        // SAFETY: 子切片与 content_raw 共享 'a 生命周期
+        let sql_raw =
+            unsafe { decode_content_bytes(&self.content_raw[..split], is_borrowed, self.encoding) };
+
+        let sql = if self.is_ora() {
+            strip_ora_prefix(sql_raw)
+        } else {
+            sql_raw
+        };
+
+        (sql, parse_indicators_from_bytes(&self.content_raw[split..]))
+    }
+
+    /// SQL 语句体在 `content_raw` 中的字节范围，以及性能指标（`sql` 字段为空）。
+    ///
+    /// 与 [`parse_performance_metrics`](Self::parse_performance_metrics) 的切分一致
+    /// （ORA 记录跳过 `": "` 前缀），但不解码正文，供列式批处理使用。
+    pub(crate) fn body_range_and_indicators(
+        &self,
+    ) -> (Range<usize>, Option<PerformanceMetrics<'static>>) {
+        let split = self.find_indicators_split();
+        let start = if self.is_ora() && self.content_raw.starts_with(b": ") {
+            2
+        } else {
+            0
+        };
+        let indicators = parse_indicators_from_bytes(&self.content_raw[split..]);
+        (start.min(split)..split, indicators)
+    }
+
+    /// 按 key 查找单个元数据字段（如 `b"user"`），不构建完整的 [`MetaParts`]
+    pub(crate) fn meta_value(&self, key: &[u8]) -> Option<&str> {
+        self.meta_raw.split(' ').find_map(|token| {
+            let (k, v) = token.split_once(':')?;
+            (k.as_bytes() == key).then_some(v)
+        })
+    }
+
     // ── Private helpers ───────────────────────────────────────────────────────
 
     fn find_indicators_split(&self) -> usize {
@@ -321,25 +446,25 @@ unsafe fn decode_content_bytes<'a>(
                 unsafe { Cow::Owned(std::str::from_utf8_unchecked(bytes).to_string()) }
             }
         }
-        FileEncodingHint::Auto => match simd_from_utf8(bytes) {
-            Ok(_) => {
-                if is_borrowed {
-                    unsafe {
-                        Cow::Borrowed(std::str::from_utf8_unchecked(std::slice::from_raw_parts(
-                            bytes.as_ptr(),
-                            bytes.len(),
-                        )))
+        FileEncodingHint::Auto | FileEncodingHint::PerRecord | FileEncodingHint::Utf8Lossy => {
+            match simd_from_utf8(bytes) {
+                Ok(_) => {
+                    if is_borrowed {
+                        unsafe {
+                            Cow::Borrowed(std::str::from_utf8_unchecked(
+                                std::slice::from_raw_parts(bytes.as_ptr(), bytes.len()),
+                            ))
+                        }
+                    } else {
+                        unsafe { Cow::Owned(std::str::from_utf8_unchecked(bytes).to_string()) }
                     }
-                } else {
-                    unsafe { Cow::Owned(std::str::from_utf8_unchecked(bytes).to_string()) }
                 }
+                Err(_) => Cow::Owned(String::from_utf8_lossy(bytes).into_owned()),
             }
-            Err(_) => Cow::Owned(String::from_utf8_lossy(bytes).into_owned()),
-        },
-        FileEncodingHint::Gb18030 => match GB18030.decode(bytes, DecoderTrap::Strict) {
-            Ok(s) => Cow::Owned(s),
-            Err(_) => Cow::Owned(String::from_utf8_lossy(bytes).into_owned()),
-        },
+        }
+        FileEncodingHint::Gb18030 | FileEncodingHint::Gbk | FileEncodingHint::Big5 => {
+            Cow::Owned(decode_legacy(encoding, bytes))
+        }
     }
 }
 
@@ -403,35 +528,202 @@ fn strip_ora_prefix(s: Cow<'_, str>) -> Cow<'_, str> {
     }
 }
 
+/// `content_raw` 按字节串序列化：二进制格式（MessagePack 等）紧凑且可借用，
+/// JSON 等不支持字节串的格式退化为数组。
+#[cfg(feature = "serde")]
+mod content_bytes {
+    use std::borrow::Cow;
+    use std::fmt;
+
+    use serde::de::{SeqAccess, Visitor};
+    use serde::{Deserializer, Serializer};
+
+    pub(super) fn serialize<S: Serializer>(bytes: &[u8], s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_bytes(bytes)
+    }
+
+    pub(super) fn deserialize<'de: 'a, 'a, D: Deserializer<'de>>(
+        d: D,
+    ) -> Result<Cow<'a, [u8]>, D::Error> {
+        struct BytesVisitor;
+
+        impl<'de> Visitor<'de> for BytesVisitor {
+            type Value = Cow<'de, [u8]>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a byte array")
+            }
+
+            fn visit_borrowed_bytes<E>(self, v: &'de [u8]) -> Result<Self::Value, E> {
+                Ok(Cow::Borrowed(v))
+            }
+
+            fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E> {
+                Ok(Cow::Owned(v.to_vec()))
+            }
+
+            fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+                Ok(Cow::Owned(v))
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let mut out = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+                while let Some(b) = seq.next_element()? {
+                    out.push(b);
+                }
+                Ok(Cow::Owned(out))
+            }
+        }
+
+        d.deserialize_bytes(BytesVisitor)
+    }
+}
+
+/// `Sqllog` 的反序列化：先读入与字段一一对应的中间结构，校验后再构造记录。
+///
+/// `encoding` 决定 `body()` 等方法是否跳过 UTF-8 校验，不能直接信任输入。
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use std::borrow::Cow;
+
+    use serde::de::Error as _;
+    use serde::{Deserialize, Deserializer};
+    use simdutf8::basic::from_utf8 as simd_from_utf8;
+
+    use super::Sqllog;
+    use crate::parser::FileEncodingHint;
+
+    /// 编码 hint 按名称序列化
+    pub(super) mod hint {
+        use serde::de::Error as _;
+        use serde::{Deserialize, Deserializer, Serializer};
+
+        use crate::parser::FileEncodingHint;
+
+        const VARIANTS: &[&str] = &[
+            "Auto",
+            "Utf8",
+            "Utf8Lossy",
+            "Gb18030",
+            "Gbk",
+            "Big5",
+            "PerRecord",
+        ];
+
+        pub(crate) fn serialize<S: Serializer>(
+            hint: &FileEncodingHint,
+            s: S,
+        ) -> Result<S::Ok, S::Error> {
+            s.serialize_str(match hint {
+                FileEncodingHint::Auto => "Auto",
+                FileEncodingHint::Utf8 => "Utf8",
+                FileEncodingHint::Utf8Lossy => "Utf8Lossy",
+                FileEncodingHint::Gb18030 => "Gb18030",
+                FileEncodingHint::Gbk => "Gbk",
+                FileEncodingHint::Big5 => "Big5",
+                FileEncodingHint::PerRecord => "PerRecord",
+            })
+        }
+
+        pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+            d: D,
+        ) -> Result<FileEncodingHint, D::Error> {
+            let name = <std::borrow::Cow<'de, str>>::deserialize(d)?;
+            Ok(match name.as_ref() {
+                "Auto" => FileEncodingHint::Auto,
+                "Utf8" => FileEncodingHint::Utf8,
+                "Utf8Lossy" => FileEncodingHint::Utf8Lossy,
+                "Gb18030" => FileEncodingHint::Gb18030,
+                "Gbk" => FileEncodingHint::Gbk,
+                "Big5" => FileEncodingHint::Big5,
+                "PerRecord" => FileEncodingHint::PerRecord,
+                other => return Err(D::Error::unknown_variant(other, VARIANTS)),
+            })
+        }
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename = "Sqllog")]
+    struct Repr<'a> {
+        #[serde(borrow)]
+        ts: Cow<'a, str>,
+        #[serde(borrow)]
+        meta_raw: Cow<'a, str>,
+        #[serde(borrow, with = "super::content_bytes")]
+        content_raw: Cow<'a, [u8]>,
+        #[serde(borrow)]
+        tag: Option<Cow<'a, str>>,
+        #[serde(with = "hint")]
+        encoding: FileEncodingHint,
+        lossy: bool,
+        utc_offset: i32,
+    }
+
+    impl<'de: 'a, 'a> Deserialize<'de> for Sqllog<'a> {
+        fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+            let repr = Repr::deserialize(d)?;
+            if repr.utc_offset.unsigned_abs() >= 86_400 {
+                return Err(D::Error::custom(format_args!(
+                    "utc_offset must be within ±24h, got {}s",
+                    repr.utc_offset
+                )));
+            }
+            let mut encoding = repr.encoding;
+            let mut lossy = repr.lossy;
+            if encoding == FileEncodingHint::Utf8 && simd_from_utf8(&repr.content_raw).is_err() {
+                encoding = FileEncodingHint::Utf8Lossy;
+                lossy = true;
+            }
+            Ok(Sqllog {
+                ts: repr.ts,
+                meta_raw: repr.meta_raw,
+                content_raw: repr.content_raw,
+                tag: repr.tag,
+                encoding,
+                lossy,
+                utc_offset: repr.utc_offset,
+            })
+        }
+    }
+}
+
 // ── Public types ──────────────────────────────────────────────────────────────
 
 /// 元数据部分
 ///
 /// 包含日志记录的所有元数据字段，如会话 ID、用户名等。
 #[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MetaParts<'a> {
     /// EP（Execution Point）编号，范围 0-255
     pub ep: u8,
 
     /// 会话 ID
+    #[cfg_attr(feature = "serde", serde(borrow))]
     pub sess_id: Cow<'a, str>,
 
     /// 线程 ID
+    #[cfg_attr(feature = "serde", serde(borrow))]
     pub thrd_id: Cow<'a, str>,
 
     /// 用户名
+    #[cfg_attr(feature = "serde", serde(borrow))]
     pub username: Cow<'a, str>,
 
     /// 事务 ID
+    #[cfg_attr(feature = "serde", serde(borrow))]
     pub trxid: Cow<'a, str>,
 
     /// 语句 ID
+    #[cfg_attr(feature = "serde", serde(borrow))]
     pub statement: Cow<'a, str>,
 
     /// 应用程序名称
+    #[cfg_attr(feature = "serde", serde(borrow))]
     pub appname: Cow<'a, str>,
 
     /// 客户端 IP 地址（可选）
+    #[cfg_attr(feature = "serde", serde(borrow))]
     pub client_ip: Cow<'a, str>,
 }
 
@@ -439,6 +731,7 @@ pub struct MetaParts<'a> {
 ///
 /// 包含 SQL 执行的性能指标，如执行时间、影响行数、执行 ID 和完整的 SQL 语句。
 #[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PerformanceMetrics<'a> {
     /// 执行时间（毫秒）
     pub exectime: f32,
@@ -450,5 +743,6 @@ pub struct PerformanceMetrics<'a> {
     pub exec_id: i64,
 
     /// 完整的 SQL 语句
+    #[cfg_attr(feature = "serde", serde(borrow))]
     pub sql: Cow<'a, str>,
 }
@@ -0,0 +1,107 @@
+//! 语句类型分类
+
+use crate::sqllog::Sqllog;
+use crate::tag::SqllogTag;
+
+/// 语句类型
+///
+/// 由 [`Sqllog::statement_kind`] 根据标签或 SQL 首个关键字得出。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum StatementKind {
+    /// `SELECT`，以及以 `WITH` 开头的查询
+    Select,
+    Insert,
+    Update,
+    Delete,
+    Merge,
+    /// `CREATE` / `ALTER` / `DROP` / `TRUNCATE` / `RENAME` / `COMMENT` / `GRANT` / `REVOKE`
+    Ddl,
+    Commit,
+    Rollback,
+    /// `CALL` / `EXEC` / `EXECUTE`，以及 `BEGIN` / `DECLARE` 开头的匿名块
+    Call,
+    Set,
+    Other,
+}
+
+impl StatementKind {
+    /// 按 SQL 首个关键字分类，跳过前导空白、注释、括号与 ORA 记录的 `": "` 前缀
+    ///
+    /// ```rust
+    /// use dm_database_parser_sqllog::StatementKind;
+    ///
+    /// assert_eq!(StatementKind::from_sql("/* hint */ (select 1)"), StatementKind::Select);
+    /// assert_eq!(StatementKind::from_sql("truncate table t"), StatementKind::Ddl);
+    /// ```
+    pub fn from_sql(sql: &str) -> Self {
+        Self::from_bytes(sql.as_bytes())
+    }
+
+    /// 只检查 ASCII 关键字，因此可直接用于遗留编码的原始字节
+    pub(crate) fn from_bytes(sql: &[u8]) -> Self {
+        const KEYWORDS: [(&str, StatementKind); 22] = [
+            ("SELECT", StatementKind::Select),
+            ("WITH", StatementKind::Select),
+            ("INSERT", StatementKind::Insert),
+            ("UPDATE", StatementKind::Update),
+            ("DELETE", StatementKind::Delete),
+            ("MERGE", StatementKind::Merge),
+            ("CREATE", StatementKind::Ddl),
+            ("ALTER", StatementKind::Ddl),
+            ("DROP", StatementKind::Ddl),
+            ("TRUNCATE", StatementKind::Ddl),
+            ("RENAME", StatementKind::Ddl),
+            ("COMMENT", StatementKind::Ddl),
+            ("GRANT", StatementKind::Ddl),
+            ("REVOKE", StatementKind::Ddl),
+            ("COMMIT", StatementKind::Commit),
+            ("ROLLBACK", StatementKind::Rollback),
+            ("CALL", StatementKind::Call),
+            ("EXEC", StatementKind::Call),
+            ("EXECUTE", StatementKind::Call),
+            ("BEGIN", StatementKind::Call),
+            ("DECLARE", StatementKind::Call),
+            ("SET", StatementKind::Set),
+        ];
+        let keyword = first_keyword(sql);
+        KEYWORDS
+            .iter()
+            .find(|(name, _)| keyword.eq_ignore_ascii_case(name.as_bytes()))
+            .map_or(StatementKind::Other, |&(_, kind)| kind)
+    }
+}
+
+/// 跳过空白、`--` / `/* */` 注释、`(` 与 `:` 后的首个 ASCII 字母串
+fn first_keyword(sql: &[u8]) -> &[u8] {
+    let mut i = 0;
+    while i < sql.len() {
+        match sql[i] {
+            b' ' | b'\t' | b'\r' | b'\n' | b'(' | b':' => i += 1,
+            b'-' if sql.get(i + 1) == Some(&b'-') => {
+                i = memchr::memchr(b'\n', &sql[i..]).map_or(sql.len(), |n| i + n + 1);
+            }
+            b'/' if sql.get(i + 1) == Some(&b'*') => {
+                i = memchr::memmem::find(&sql[i + 2..], b"*/").map_or(sql.len(), |n| i + n + 4);
+            }
+            _ => break,
+        }
+    }
+    let len = sql[i..]
+        .iter()
+        .take_while(|b| b.is_ascii_alphabetic())
+        .count();
+    &sql[i..i + len]
+}
+
+impl Sqllog<'_> {
+    /// 语句类型：有 `SEL` / `INS` / `UPD` / `DEL` / `DDL` 标签时直接采用，
+    /// 否则按 SQL 正文首个关键字判断（零分配，不解码正文）
+    pub fn statement_kind(&self) -> StatementKind {
+        self.tag
+            .as_deref()
+            .and_then(|tag| SqllogTag::known(tag.as_bytes()))
+            .and_then(|tag| tag.statement_kind())
+            .unwrap_or_else(|| StatementKind::from_bytes(&self.content_raw))
+    }
+}
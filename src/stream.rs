@@ -0,0 +1,256 @@
+//! 流式解析：从任意 `BufRead`（stdin、管道、解压流）逐条读取记录。
+//!
+//! 与基于 mmap 的 [`LogParser`](crate::LogParser) 不同，流式解析不要求整个日志位于
+//! 一块连续内存中。内部缓冲区在读取过程中复用，内存占用上限约等于最大单条记录的大小。
+
+use memchr::memchr;
+use std::fs::File;
+use std::io::{BufRead, BufReader, ErrorKind, Read};
+use std::ops::Range;
+use std::path::Path;
+
+use crate::compress::Compression;
+use crate::error::ParseError;
+use crate::parser::{
+    FINDER_RECORD_START, LogParserBuilder, ParseOptions, is_timestamp_start, parse_record_with_hint,
+};
+use crate::sqllog::Sqllog;
+
+/// 可增量追加数据的记录切分缓冲区。
+///
+/// 边界判定与 `LogIterator` 完全一致：记录以 `"\n20"` + 合法时间戳开始。
+/// 当缓冲区末尾不足以判定边界时（候选 `'\n'` 之后不足 23 字节），等待更多数据。
+#[derive(Default)]
+pub(crate) struct RecordBuffer {
+    buf: Vec<u8>,
+    /// 当前（尚未产出的）记录在 `buf` 中的起点
+    start: usize,
+    /// 下一次边界搜索的起点，避免跨多次 refill 的大记录被重复扫描
+    scan: usize,
+}
+
+impl RecordBuffer {
+    /// 追加新读入的数据；追加前丢弃已产出的记录以保持内存有界。
+    pub(crate) fn extend(&mut self, bytes: &[u8]) {
+        if self.start > 0 {
+            self.buf.drain(..self.start);
+            self.scan -= self.start;
+            self.start = 0;
+        }
+        self.buf.extend_from_slice(bytes);
+    }
+
+    /// 缓冲区中是否还有未产出的数据
+    pub(crate) fn has_pending(&self) -> bool {
+        self.start < self.buf.len()
+    }
+
+    /// 丢弃所有缓冲数据（如被跟踪文件被截断时）
+    pub(crate) fn clear(&mut self) {
+        self.buf.clear();
+        self.start = 0;
+        self.scan = 0;
+    }
+
+    /// 丢弃缓冲区开头不以时间戳开始的续行，直到第一条记录起点。
+    ///
+    /// 返回是否已定位到记录起点；数据不足以判定时返回 `false`，等待更多数据。
+    pub(crate) fn skip_to_record_start(&mut self) -> bool {
+        loop {
+            let pending = &self.buf[self.start..];
+            if pending.len() >= 23 && is_timestamp_start(&pending[..23]) {
+                return true;
+            }
+            match memchr(b'\n', pending) {
+                Some(nl) => self.start += nl + 1,
+                None if pending.len() >= 23 => self.start = self.buf.len(),
+                None => return false,
+            }
+            self.scan = self.start;
+        }
+    }
+
+    /// 取出下一条完整记录（已去除行尾 `\r`，跳过空记录）。
+    ///
+    /// `eof = false` 时，最后一条记录因无法确认是否写完而保留在缓冲区中；
+    /// `eof = true` 时，剩余数据作为最后一条记录产出。
+    pub(crate) fn next_record(&mut self, eof: bool) -> Option<&[u8]> {
+        let range = self.next_range(eof)?;
+        Some(&self.buf[range])
+    }
+
+    fn next_range(&mut self, eof: bool) -> Option<Range<usize>> {
+        loop {
+            if self.start >= self.buf.len() {
+                return None;
+            }
+
+            let from = self.scan.max(self.start);
+            let mut boundary: Option<usize> = None;
+            let mut incomplete: Option<usize> = None;
+            for candidate in FINDER_RECORD_START.find_iter(&self.buf[from..]) {
+                let nl = from + candidate;
+                let ts_start = nl + 1;
+                if ts_start + 23 > self.buf.len() {
+                    incomplete = Some(nl);
+                    break;
+                }
+                if is_timestamp_start(&self.buf[ts_start..ts_start + 23]) {
+                    boundary = Some(nl);
+                    break;
+                }
+            }
+
+            let (record_end, next_start) = match boundary {
+                Some(nl) => (nl, nl + 1),
+                None if eof => (self.buf.len(), self.buf.len()),
+                None => {
+                    // "\n20" 可能跨越本次数据末尾，保留最后 2 字节重新搜索
+                    let resume = incomplete.unwrap_or(self.buf.len().saturating_sub(2));
+                    self.scan = resume.max(self.start);
+                    return None;
+                }
+            };
+
+            let record_start = self.start;
+            self.start = next_start;
+            self.scan = next_start;
+
+            let mut end = record_end;
+            if end > record_start && self.buf[end - 1] == b'\r' {
+                end -= 1;
+            }
+            if end > record_start {
+                return Some(record_start..end);
+            }
+        }
+    }
+}
+
+/// 将单条记录切片解析为拥有所有权的 `Sqllog`。
+///
+/// `options` 来自 [`LogParserBuilder::streaming_options`]：流式场景无法预先校验整个输入，
+/// 默认逐条记录判定编码。
+pub(crate) fn parse_owned(
+    record: &[u8],
+    options: &ParseOptions,
+) -> Result<Sqllog<'static>, ParseError> {
+    let is_multiline = memchr(b'\n', record).is_some();
+    parse_record_with_hint(record, is_multiline, options).map(Sqllog::into_owned)
+}
+
+/// 基于 `BufRead` 的流式日志解析器
+///
+/// 适用于 `tail -f | tool`、`zcat | tool` 等无法 mmap 的输入。多行记录可跨越多次
+/// 读取，内部缓冲区复用，内存占用受最大单条记录大小约束。
+///
+/// ```rust,no_run
+/// use dm_database_parser_sqllog::StreamingLogParser;
+///
+/// let stdin = std::io::stdin();
+/// for result in StreamingLogParser::new(stdin.lock()) {
+///     match result {
+///         Ok(sqllog) => println!("{} {}", sqllog.ts, sqllog.body()),
+///         Err(e) => eprintln!("解析错误: {}", e),
+///     }
+/// }
+/// ```
+pub struct StreamingLogParser<R> {
+    reader: R,
+    buffer: RecordBuffer,
+    eof: bool,
+    options: ParseOptions,
+}
+
+impl<R: BufRead> StreamingLogParser<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buffer: RecordBuffer::default(),
+            eof: false,
+            options: LogParserBuilder::new().streaming_options(),
+        }
+    }
+
+    /// 使用 `builder` 中的编码、解码策略、时区与标签设置解析记录。
+    ///
+    /// 由于无法预先扫描整个输入，自动探测按逐记录判定（UTF-8 或 GB18030），
+    /// 不支持 UTF-16 与候选编码打分。
+    ///
+    /// ```rust
+    /// use dm_database_parser_sqllog::{LogParser, StreamingLogParser};
+    ///
+    /// let log = b"2025-08-12 10:57:09.548 (EP[0] sess:1 thrd:2 user:a trxid:3 stmt:4 appname:app) SELECT 1\n";
+    /// let mut parser = StreamingLogParser::new(log.as_slice())
+    ///     .options(LogParser::builder().utc_offset(8 * 3600));
+    /// assert_eq!(parser.next().unwrap().unwrap().utc_offset(), 8 * 3600);
+    /// ```
+    pub fn options(mut self, builder: LogParserBuilder) -> Self {
+        self.options = builder.streaming_options();
+        self
+    }
+
+    /// 取回内部的 reader
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl StreamingLogParser<BufReader<Box<dyn Read + Send>>> {
+    /// 以流式方式打开日志文件，压缩归档（gzip / zstd / xz）按魔数识别并边读边解压。
+    ///
+    /// 与 [`LogParser::from_path`](crate::LogParser::from_path) 不同，解压结果不会整体
+    /// 驻留内存，适合体积远大于可用内存的归档。
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, ParseError> {
+        let mut file = File::open(path).map_err(|e| ParseError::IoError(e.to_string()))?;
+        let mut magic = [0u8; 6];
+        let mut filled = 0;
+        while filled < magic.len() {
+            match file.read(&mut magic[filled..]) {
+                Ok(0) => break,
+                Ok(n) => filled += n,
+                Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => return Err(ParseError::IoError(e.to_string())),
+            }
+        }
+        let compression = Compression::detect(&magic[..filled]);
+        // 魔数字节已被读出，拼回流的开头再交给解码器
+        let reader = std::io::Cursor::new(magic[..filled].to_vec()).chain(file);
+        Ok(Self::new(BufReader::with_capacity(
+            64 * 1024,
+            compression.decoder(reader)?,
+        )))
+    }
+}
+
+impl<R: BufRead> Iterator for StreamingLogParser<R> {
+    type Item = Result<Sqllog<'static>, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(record) = self.buffer.next_record(self.eof) {
+                return Some(parse_owned(record, &self.options));
+            }
+            if self.eof {
+                return None;
+            }
+
+            let chunk = match self.reader.fill_buf() {
+                Ok(chunk) => chunk,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => {
+                    // 读取失败后不再继续读取，剩余缓冲数据按 EOF 处理
+                    self.eof = true;
+                    return Some(Err(ParseError::IoError(e.to_string())));
+                }
+            };
+            if chunk.is_empty() {
+                self.eof = true;
+                continue;
+            }
+            let len = chunk.len();
+            self.buffer.extend(chunk);
+            self.reader.consume(len);
+        }
+    }
+}
@@ -0,0 +1,318 @@
+//! 从 SQL 文本中提取引用的表
+//!
+//! 基于 [`crate::lexer`] 的 token 序列做关键字模式匹配，不构建语法树：覆盖
+//! `SELECT` / `INSERT` / `UPDATE` / `DELETE` / `MERGE` 与 `CREATE` / `ALTER` / `DROP` /
+//! `TRUNCATE` 等常见写法，以及 `CREATE INDEX` / `GRANT` / `REVOKE` / `COMMENT` 中
+//! `ON` 之后的对象，足以用于访问审计，但不保证识别所有语法。
+
+use std::fmt;
+
+use crate::lexer::{Lexer, Token, TokenKind};
+use crate::sqllog::Sqllog;
+use crate::statement::StatementKind;
+
+/// 对表的访问方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TableAccess {
+    /// 出现在 `FROM` / `JOIN` / `MERGE ... USING` 中
+    Read,
+    /// 被 DML 修改，或是 DDL（含授权、注释）的目标对象
+    Write,
+}
+
+/// SQL 中引用的一张表
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TableRef {
+    /// 模式名；未限定时为 `None`
+    pub schema: Option<String>,
+    /// 表名
+    pub name: String,
+    pub access: TableAccess,
+}
+
+impl fmt::Display for TableRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(schema) = &self.schema {
+            write!(f, "{schema}.")?;
+        }
+        f.write_str(&self.name)
+    }
+}
+
+/// 表名之后、不可能是别名的关键字
+const CLAUSE_WORDS: [&str; 30] = [
+    "where",
+    "join",
+    "inner",
+    "left",
+    "right",
+    "full",
+    "cross",
+    "natural",
+    "outer",
+    "on",
+    "using",
+    "group",
+    "order",
+    "having",
+    "union",
+    "intersect",
+    "minus",
+    "except",
+    "connect",
+    "start",
+    "for",
+    "limit",
+    "offset",
+    "fetch",
+    "set",
+    "values",
+    "select",
+    "when",
+    "partition",
+    "returning",
+];
+
+/// 提取 SQL 引用的表，按首次出现的顺序去重
+///
+/// - 未加引号的名称按 DM 的规则转为大写，带引号的去除引号、保留原样
+/// - `schema.table` 拆分为模式与表名；`WITH` 定义的公用表表达式不计入
+/// - 同一张表既被读又被写时各出现一次
+///
+/// ```rust
+/// use dm_database_parser_sqllog::{TableAccess, referenced_tables};
+///
+/// let tables = referenced_tables(
+///     "INSERT INTO app.\"Orders\" SELECT * FROM staging o JOIN app.users u ON o.uid = u.id",
+/// );
+/// let names: Vec<_> = tables.iter().map(|t| (t.to_string(), t.access)).collect();
+/// assert_eq!(
+///     names,
+///     [
+///         ("APP.Orders".to_string(), TableAccess::Write),
+///         ("STAGING".to_string(), TableAccess::Read),
+///         ("APP.USERS".to_string(), TableAccess::Read),
+///     ]
+/// );
+/// ```
+pub fn referenced_tables(sql: &str) -> Vec<TableRef> {
+    let tokens: Vec<Token<'_>> = Lexer::code(sql).collect();
+    let kind = StatementKind::from_sql(sql);
+    let mut scan = Scanner {
+        tokens: &tokens,
+        refs: Vec::new(),
+        ctes: Vec::new(),
+    };
+
+    // 括号内是否为子查询；FROM 只在查询上下文中生效，以排除 `extract(year from d)` 之类
+    let mut queries: Vec<bool> = Vec::new();
+    let mut multi_insert = false;
+    // GRANT / REVOKE 中的 `CREATE TABLE`、`FROM user` 是权限与被授权者，不是表
+    let head = tokens.first().map(|t| t.text.to_ascii_lowercase());
+    let privileges = matches!(head.as_deref(), Some("grant" | "revoke"));
+    // 下一个 `ON` 之后是否为对象名：授权、注释，以及 `CREATE INDEX`
+    let mut on_object = privileges || head.as_deref() == Some("comment");
+    let mut i = 0;
+
+    while i < tokens.len() {
+        let token = tokens[i];
+        let prev = i.checked_sub(1).map(|p| tokens[p]);
+        let next = tokens.get(i + 1);
+        i += 1;
+
+        if token.kind == TokenKind::Punct {
+            match token.text {
+                "(" => queries.push(next.is_some_and(|t| t.is_word("select") || t.is_word("with"))),
+                ")" => {
+                    queries.pop();
+                }
+                ";" => multi_insert = false,
+                _ => {}
+            }
+            continue;
+        }
+        if token.kind != TokenKind::Word {
+            continue;
+        }
+
+        // `name AS (` 紧跟 WITH 或逗号时为公用表表达式
+        if token.is_word("as")
+            && next.is_some_and(|t| t.is_punct("("))
+            && i >= 3
+            && (tokens[i - 3].is_word("with")
+                || tokens[i - 3].is_word("recursive")
+                || tokens[i - 3].is_punct(","))
+            && let Some(name) = ident(&tokens[i - 2])
+        {
+            scan.ctes.push(name);
+            continue;
+        }
+
+        let word = token.text.to_ascii_lowercase();
+        match word.as_str() {
+            "from" | "join" if !privileges && queries.last().copied().unwrap_or(true) => {
+                i = scan.table_list(i, word == "from");
+            }
+            "into"
+                if multi_insert
+                    || prev.is_some_and(|p| p.is_word("insert") || p.is_word("merge")) =>
+            {
+                i = scan.name(i, TableAccess::Write).unwrap_or(i);
+            }
+            "insert" if next.is_some_and(|t| t.is_word("all") || t.is_word("first")) => {
+                multi_insert = true;
+            }
+            "update"
+                if !prev.is_some_and(|p| p.is_word("for") || p.is_word("on"))
+                    && !next.is_some_and(|t| t.is_word("set")) =>
+            {
+                i = scan.name(i, TableAccess::Write).unwrap_or(i);
+            }
+            "delete" if !prev.is_some_and(|p| p.is_word("on")) => {
+                if next.is_some_and(|t| t.is_word("from")) {
+                    i += 1;
+                }
+                if !tokens.get(i).is_some_and(|t| t.is_word("where")) {
+                    i = scan.name(i, TableAccess::Write).unwrap_or(i);
+                }
+            }
+            "using" if kind == StatementKind::Merge => {
+                i = scan.name(i, TableAccess::Read).unwrap_or(i);
+            }
+            "index" if head.as_deref() == Some("create") => on_object = true,
+            "on" if on_object && queries.is_empty() => {
+                on_object = false;
+                if tokens.get(i).is_some_and(|t| t.is_word("column")) {
+                    i = scan.column(i + 1, TableAccess::Write).unwrap_or(i + 1);
+                    continue;
+                }
+                if tokens
+                    .get(i)
+                    .is_some_and(|t| t.is_word("table") || t.is_word("view"))
+                {
+                    i += 1;
+                }
+                i = scan.name(i, TableAccess::Write).unwrap_or(i);
+            }
+            "table" | "view" if kind == StatementKind::Ddl && !privileges => {
+                while tokens
+                    .get(i)
+                    .is_some_and(|t| t.is_word("if") || t.is_word("not") || t.is_word("exists"))
+                {
+                    i += 1;
+                }
+                i = scan.name(i, TableAccess::Write).unwrap_or(i);
+            }
+            _ => {}
+        }
+    }
+
+    let Scanner { refs, ctes, .. } = scan;
+    let mut out: Vec<TableRef> = Vec::with_capacity(refs.len());
+    for r in refs {
+        let cte = r.schema.is_none() && ctes.contains(&r.name);
+        if !cte && !out.contains(&r) {
+            out.push(r);
+        }
+    }
+    out
+}
+
+struct Scanner<'t, 's> {
+    tokens: &'t [Token<'s>],
+    refs: Vec<TableRef>,
+    ctes: Vec<String>,
+}
+
+impl Scanner<'_, '_> {
+    /// 从 `i` 处读取以 `.` 分隔的标识符，返回各部分与下一个 token 的位置
+    fn qualified(&self, i: usize) -> Option<(Vec<String>, usize)> {
+        let mut parts = vec![ident(self.tokens.get(i)?)?];
+        let mut i = i + 1;
+        while self.tokens.get(i).is_some_and(|t| t.is_punct("."))
+            && let Some(part) = self.tokens.get(i + 1).and_then(ident)
+        {
+            parts.push(part);
+            i += 2;
+        }
+        Some((parts, i))
+    }
+
+    /// 从 `i` 处读取 `[schema.]name`，记录后返回下一个 token 的位置
+    fn name(&mut self, i: usize, access: TableAccess) -> Option<usize> {
+        let (parts, i) = self.qualified(i)?;
+        self.push(parts, access);
+        Some(i)
+    }
+
+    /// 从 `i` 处读取 `[schema.]table.column`，记录其中的表
+    fn column(&mut self, i: usize, access: TableAccess) -> Option<usize> {
+        let (mut parts, i) = self.qualified(i)?;
+        parts.pop();
+        self.push(parts, access);
+        Some(i)
+    }
+
+    fn push(&mut self, mut parts: Vec<String>, access: TableAccess) {
+        if let Some(name) = parts.pop() {
+            self.refs.push(TableRef {
+                schema: parts.pop(),
+                name,
+                access,
+            });
+        }
+    }
+
+    /// `FROM` / `JOIN` 之后的表；`list` 为真时继续读取逗号分隔的后续表
+    fn table_list(&mut self, mut i: usize, list: bool) -> usize {
+        loop {
+            // `FROM (select ...)` 由外层循环继续扫描；`FROM TABLE(f())` 等表函数跳过
+            let start = self.refs.len();
+            let Some(next) = self.name(i, TableAccess::Read) else {
+                return i;
+            };
+            if self.tokens.get(next).is_some_and(|t| t.is_punct("(")) {
+                self.refs.truncate(start);
+                return i;
+            }
+            i = next;
+
+            if self.tokens.get(i).is_some_and(|t| t.is_word("as")) {
+                i += 1;
+            }
+            if let Some(alias) = self.tokens.get(i)
+                && (alias.kind == TokenKind::QuotedIdent
+                    || (alias.kind == TokenKind::Word
+                        && !CLAUSE_WORDS.iter().any(|w| alias.is_word(w))))
+            {
+                i += 1;
+            }
+            if !list || !self.tokens.get(i).is_some_and(|t| t.is_punct(",")) {
+                return i;
+            }
+            i += 1;
+        }
+    }
+}
+
+/// 标识符的规范名称：未加引号的转大写，带引号的去除引号与 `""` 转义
+fn ident(token: &Token<'_>) -> Option<String> {
+    match token.kind {
+        TokenKind::Word => Some(token.text.to_ascii_uppercase()),
+        TokenKind::QuotedIdent => {
+            let inner = token.text.strip_prefix('"')?;
+            let inner = inner.strip_suffix('"').unwrap_or(inner);
+            Some(inner.replace("\"\"", "\""))
+        }
+        _ => None,
+    }
+}
+
+impl Sqllog<'_> {
+    /// SQL 语句体（不含性能指标，ORA 记录已去除 `": "` 前缀）引用的表，参见 [`referenced_tables`]
+    pub fn tables(&self) -> Vec<TableRef> {
+        referenced_tables(&self.sql_and_indicators().0)
+    }
+}
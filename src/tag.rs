@@ -0,0 +1,164 @@
+//! DM sqllog 记录标签
+
+use std::fmt;
+use std::sync::Arc;
+
+use crate::sqllog::Sqllog;
+use crate::statement::StatementKind;
+
+/// 记录开头的方括号标签
+///
+/// DM 在部分记录的 SQL 前写入 `[SEL]`、`[ORA]` 等标签；未收录的标签保存在 `Other` 中。
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SqllogTag {
+    /// `SEL`：查询
+    Sel,
+    /// `INS`：插入
+    Ins,
+    /// `UPD`：更新
+    Upd,
+    /// `DEL`：删除
+    Del,
+    /// `DDL`：数据定义语句
+    Ddl,
+    /// `ORA`：Oracle 兼容模式执行的语句（正文以 `": "` 开头）
+    Ora,
+    /// 其他方括号标记，保留原文
+    Other(String),
+}
+
+/// 已收录的标签，顺序即 [`TagFilter`] 中的位序
+const KNOWN: [(&str, SqllogTag); 6] = [
+    ("SEL", SqllogTag::Sel),
+    ("INS", SqllogTag::Ins),
+    ("UPD", SqllogTag::Upd),
+    ("DEL", SqllogTag::Del),
+    ("DDL", SqllogTag::Ddl),
+    ("ORA", SqllogTag::Ora),
+];
+
+/// [`TagFilter`] 中表示"接受任意未收录标记"的位，仅默认过滤器设置
+const ANY_OTHER_BIT: u8 = 1 << KNOWN.len();
+
+impl SqllogTag {
+    /// 解析标签文本（已收录的标签不区分大小写）
+    pub fn parse(tag: &str) -> Self {
+        Self::known(tag.as_bytes()).unwrap_or_else(|| SqllogTag::Other(tag.to_string()))
+    }
+
+    /// 已收录的标签，不分配
+    pub(crate) fn known(tag: &[u8]) -> Option<Self> {
+        KNOWN
+            .iter()
+            .find(|(name, _)| tag.eq_ignore_ascii_case(name.as_bytes()))
+            .map(|(_, tag)| tag.clone())
+    }
+
+    /// 标签文本；已收录的标签为大写形式
+    pub fn as_str(&self) -> &str {
+        match self {
+            SqllogTag::Sel => "SEL",
+            SqllogTag::Ins => "INS",
+            SqllogTag::Upd => "UPD",
+            SqllogTag::Del => "DEL",
+            SqllogTag::Ddl => "DDL",
+            SqllogTag::Ora => "ORA",
+            SqllogTag::Other(s) => s,
+        }
+    }
+
+    /// 标签隐含的语句类型；`ORA` 与未收录的标签返回 `None`
+    pub fn statement_kind(&self) -> Option<StatementKind> {
+        match self {
+            SqllogTag::Sel => Some(StatementKind::Select),
+            SqllogTag::Ins => Some(StatementKind::Insert),
+            SqllogTag::Upd => Some(StatementKind::Update),
+            SqllogTag::Del => Some(StatementKind::Delete),
+            SqllogTag::Ddl => Some(StatementKind::Ddl),
+            SqllogTag::Ora | SqllogTag::Other(_) => None,
+        }
+    }
+}
+
+/// 已收录标签在 [`TagFilter`] 中的位；未收录时返回 `None`
+fn known_bit(tag: &[u8]) -> Option<u8> {
+    KNOWN
+        .iter()
+        .position(|(name, _)| tag.eq_ignore_ascii_case(name.as_bytes()))
+        .map(|i| 1 << i)
+}
+
+impl fmt::Display for SqllogTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// 解析器识别为标签的方括号标记集合
+///
+/// 已收录的标签按位存储；列出的 `Other` 标记按名称保存（不区分大小写），
+/// 以 `Arc` 共享，复制解析选项时不必重新分配。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TagFilter {
+    bits: u8,
+    others: Arc<[String]>,
+}
+
+impl Default for TagFilter {
+    /// 默认接受任意方括号标记
+    fn default() -> Self {
+        Self {
+            bits: u8::MAX,
+            others: Arc::from([]),
+        }
+    }
+}
+
+impl TagFilter {
+    pub(crate) fn from_tags<I: IntoIterator<Item = SqllogTag>>(tags: I) -> Self {
+        let mut bits = 0;
+        let mut others = Vec::new();
+        for tag in tags {
+            match tag {
+                SqllogTag::Other(name) => match known_bit(name.as_bytes()) {
+                    Some(bit) => bits |= bit,
+                    None => others.push(name),
+                },
+                known => bits |= known_bit(known.as_str().as_bytes()).unwrap_or(0),
+            }
+        }
+        Self {
+            bits,
+            others: others.into(),
+        }
+    }
+
+    /// 方括号内的原始字节是否应作为标签提取
+    pub(crate) fn accepts(&self, inner: &[u8]) -> bool {
+        match known_bit(inner) {
+            Some(bit) => self.bits & bit != 0,
+            None => {
+                self.bits & ANY_OTHER_BIT != 0
+                    || self
+                        .others
+                        .iter()
+                        .any(|name| inner.eq_ignore_ascii_case(name.as_bytes()))
+            }
+        }
+    }
+}
+
+impl Sqllog<'_> {
+    /// 类型化的标签；没有标签时返回 `None`
+    pub fn tag_kind(&self) -> Option<SqllogTag> {
+        self.tag.as_deref().map(SqllogTag::parse)
+    }
+
+    /// 是否为 `ORA` 标签（不区分大小写，不分配）
+    pub(crate) fn is_ora(&self) -> bool {
+        self.tag
+            .as_deref()
+            .is_some_and(|tag| SqllogTag::known(tag.as_bytes()) == Some(SqllogTag::Ora))
+    }
+}
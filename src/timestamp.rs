@@ -0,0 +1,84 @@
+//! 时间戳解析
+//!
+//! sqllog 时间戳为服务器本地时间 `"YYYY-MM-DD HH:MM:SS.mmm"`，不带时区；
+//! 时区由 [`LogParserBuilder::utc_offset`](crate::LogParserBuilder::utc_offset) 指定。
+
+/// 将 23 字节时间戳按 UTC 解释为 Unix 毫秒数（不做时区换算）。
+///
+/// 字段越界（如 13 月、2 月 30 日、25 时）或含非数字时返回 `None`。
+pub(crate) fn parse_ts_millis(ts: &[u8]) -> Option<i64> {
+    if ts.len() < 23 {
+        return None;
+    }
+    let year = digits(&ts[0..4])? as i64;
+    let month = digits(&ts[5..7])?;
+    let day = digits(&ts[8..10])?;
+    let hour = digits(&ts[11..13])? as i64;
+    let minute = digits(&ts[14..16])? as i64;
+    let second = digits(&ts[17..19])? as i64;
+    let millis = digits(&ts[20..23])? as i64;
+
+    if !(1..=12).contains(&month)
+        || day == 0
+        || day > days_in_month(year, month)
+        || hour > 23
+        || minute > 59
+        || second > 59
+    {
+        return None;
+    }
+
+    let days = days_from_civil(year, month, day);
+    Some(((days * 24 + hour) * 60 + minute) * 60_000 + second * 1000 + millis)
+}
+
+/// 解析用户给出的时间边界，允许省略尾部字段（如 `"2025-08-12 14:00"`），省略部分取最小值。
+///
+/// 只能在字段边界处截断：`"2025-08-1"` 这类截断在字段中间的输入返回 `None`。
+pub(crate) fn parse_bound(bound: &str) -> Option<i64> {
+    const TEMPLATE: &[u8; 23] = b"0000-01-01 00:00:00.000";
+    /// 完整字段结束处的长度：`YYYY`、`YYYY-MM`、`YYYY-MM-DD`、`… HH`、`… HH:MM`、`… HH:MM:SS`、完整时间戳
+    const FIELD_ENDS: [usize; 7] = [4, 7, 10, 13, 16, 19, 23];
+    let bytes = bound.as_bytes();
+    if !FIELD_ENDS.contains(&bytes.len()) {
+        return None;
+    }
+    let mut full = *TEMPLATE;
+    full[..bytes.len()].copy_from_slice(bytes);
+    // 分隔符必须与模板一致，防止 "2025/08/12" 之类被误读
+    if full
+        .iter()
+        .zip(TEMPLATE)
+        .any(|(b, t)| !t.is_ascii_digit() && b != t)
+    {
+        return None;
+    }
+    parse_ts_millis(&full)
+}
+
+#[inline]
+fn digits(bytes: &[u8]) -> Option<u32> {
+    bytes.iter().try_fold(0u32, |acc, &b| {
+        b.is_ascii_digit().then(|| acc * 10 + (b - b'0') as u32)
+    })
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// 公历日期距 1970-01-01 的天数（Howard Hinnant 的 `days_from_civil` 算法）
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let m = month as i64;
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
@@ -0,0 +1,97 @@
+//! 记录的扁平化序列化视图（需启用 `serde` feature）
+
+use std::borrow::Cow;
+
+use serde::{Deserialize, Serialize};
+
+use crate::sqllog::Sqllog;
+
+/// [`Sqllog`] 展开后的扁平视图
+///
+/// 序列化解析后的元数据、SQL 与性能指标，而不是 `meta_raw` / `content_raw` 原始字节，
+/// 适合输出 JSON、MessagePack 或写入消息总线。字符串字段尽量借用原记录。
+///
+/// ```rust
+/// use dm_database_parser_sqllog::parse_record;
+///
+/// let record = parse_record(
+///     b"2025-08-12 10:57:09.548 (EP[0] sess:1 thrd:2 user:alice trxid:3 stmt:4 appname:app) SELECT 1",
+/// )
+/// .unwrap();
+/// let view = record.view();
+/// assert_eq!(view.username, "alice");
+/// assert_eq!(view.sql, "SELECT 1");
+/// assert_eq!(view.exectime, None);
+/// ```
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct SqllogView<'a> {
+    /// 时间戳，格式为 "YYYY-MM-DD HH:MM:SS.mmm"
+    #[serde(borrow)]
+    pub ts: Cow<'a, str>,
+
+    /// EP（Execution Point）编号
+    pub ep: u8,
+
+    #[serde(borrow)]
+    pub sess_id: Cow<'a, str>,
+
+    #[serde(borrow)]
+    pub thrd_id: Cow<'a, str>,
+
+    #[serde(borrow)]
+    pub username: Cow<'a, str>,
+
+    #[serde(borrow)]
+    pub trxid: Cow<'a, str>,
+
+    #[serde(borrow)]
+    pub statement: Cow<'a, str>,
+
+    #[serde(borrow)]
+    pub appname: Cow<'a, str>,
+
+    /// 客户端 IP，记录中没有 `ip` 字段时为 `None`
+    #[serde(borrow)]
+    pub client_ip: Option<Cow<'a, str>>,
+
+    /// 方括号标签（如 `SEL`、`ORA`）
+    #[serde(borrow)]
+    pub tag: Option<Cow<'a, str>>,
+
+    /// SQL 语句体（ORA 记录已去除 `": "` 前缀）
+    #[serde(borrow)]
+    pub sql: Cow<'a, str>,
+
+    /// 执行时间（毫秒），无性能指标时为 `None`
+    pub exectime: Option<f32>,
+
+    /// 影响行数，无性能指标时为 `None`
+    pub rowcount: Option<u32>,
+
+    /// 执行 ID，无性能指标时为 `None`
+    pub exec_id: Option<i64>,
+}
+
+impl<'a> Sqllog<'a> {
+    /// 解析元数据与性能指标，构建可序列化的扁平视图（需启用 `serde` feature）
+    pub fn view(&self) -> SqllogView<'a> {
+        let meta = self.parse_meta();
+        let (sql, indicators) = self.sql_and_indicators();
+        SqllogView {
+            ts: self.ts.clone(),
+            ep: meta.ep,
+            sess_id: meta.sess_id,
+            thrd_id: meta.thrd_id,
+            username: meta.username,
+            trxid: meta.trxid,
+            statement: meta.statement,
+            appname: meta.appname,
+            client_ip: (!meta.client_ip.is_empty()).then_some(meta.client_ip),
+            tag: self.tag.clone(),
+            sql,
+            exectime: indicators.as_ref().map(|pm| pm.exectime),
+            rowcount: indicators.as_ref().map(|pm| pm.rowcount),
+            exec_id: indicators.as_ref().map(|pm| pm.exec_id),
+        }
+    }
+}
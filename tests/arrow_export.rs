@@ -0,0 +1,118 @@
+#![cfg(feature = "arrow")]
+
+use arrow_array::cast::AsArray;
+use arrow_array::types::{Float32Type, Int32Type, Int64Type, TimestampMillisecondType};
+use arrow_array::{Array, RecordBatch};
+use dm_database_parser_sqllog::{LogParser, sqllog_schema};
+use rayon::prelude::*;
+
+const LOG: &str = "2025-08-12 10:57:09.548 (EP[1] sess:0x1 thrd:2 user:alice trxid:3 stmt:0x4 appname:disql ip:::ffff:10.0.0.1) [SEL] SELECT 1. EXECTIME: 1.5(ms) ROWCOUNT: 1(rows) EXEC_ID: 100.\n\
+                   2025-08-12 10:57:10.000 (EP[0] sess:0x1 thrd:2 user:alice trxid:3 stmt:0x5 appname:disql) [ORA]: SELECT *\n\
+                   FROM DUAL\n\
+                   broken line without timestamp\n\
+                   2025-08-12 10:57:11.000 (EP[0] sess:0x2 thrd:3 user:bob trxid:4 stmt:0x6 appname:jdbc) COMMIT\n";
+
+fn rows(batches: &[RecordBatch]) -> usize {
+    batches.iter().map(RecordBatch::num_rows).sum()
+}
+
+#[test]
+fn schema_is_stable() {
+    let names: Vec<_> = sqllog_schema()
+        .fields()
+        .iter()
+        .map(|f| f.name().clone())
+        .collect();
+    assert_eq!(
+        names,
+        [
+            "ts",
+            "ep",
+            "sess_id",
+            "thrd_id",
+            "username",
+            "trxid",
+            "statement",
+            "appname",
+            "client_ip",
+            "tag",
+            "sql",
+            "exectime",
+            "rowcount",
+            "exec_id"
+        ]
+    );
+}
+
+#[test]
+fn batches_follow_file_order() {
+    let parser = LogParser::builder()
+        .utc_offset(8 * 3600)
+        .open_bytes(LOG.as_bytes());
+    let results: Vec<_> = parser.arrow_batches(2).collect();
+    // 不以时间戳开头的行属于上一条多行记录，不产生解析错误
+    assert_eq!(results.len(), 2);
+    let batches: Vec<RecordBatch> = results.into_iter().map(Result::unwrap).collect();
+    assert_eq!(rows(&batches), 3);
+
+    let first = &batches[0];
+    assert_eq!(first.schema(), sqllog_schema());
+
+    let ts = first.column(0).as_primitive::<TimestampMillisecondType>();
+    assert_eq!(ts.value(0), 1_754_996_229_548 - 8 * 3_600_000);
+
+    let users = first.column(4).as_dictionary::<Int32Type>();
+    assert_eq!(users.values().len(), 1, "username is dictionary-encoded");
+    let user_values = users.values().as_string::<i32>();
+    assert_eq!(user_values.value(users.keys().value(1) as usize), "alice");
+
+    let ip = first.column(8).as_string::<i32>();
+    assert_eq!(ip.value(0), "::ffff:10.0.0.1");
+    assert!(ip.is_null(1));
+
+    let sql = first.column(10).as_string::<i32>();
+    assert_eq!(
+        sql.value(1),
+        "SELECT *\nFROM DUAL\nbroken line without timestamp"
+    );
+
+    let exectime = first.column(11).as_primitive::<Float32Type>();
+    assert_eq!(exectime.value(0), 1.5);
+    assert!(exectime.is_null(1));
+    let exec_id = first.column(13).as_primitive::<Int64Type>();
+    assert_eq!(exec_id.value(0), 100);
+
+    let tag = batches[1].column(9);
+    assert!(tag.is_null(0));
+}
+
+#[test]
+fn parse_errors_are_yielded_in_place() {
+    let log = format!("garbage\n{LOG}");
+    let parser = LogParser::from_bytes(log.into_bytes());
+    let results: Vec<_> = parser.arrow_batches(10).collect();
+    assert!(results[0].is_err());
+    assert_eq!(rows(&[results[1].clone().unwrap()]), 3);
+}
+
+#[test]
+fn parallel_batches_cover_all_rows() {
+    let mut log = String::new();
+    for i in 0..5000 {
+        log.push_str(&format!(
+            "2025-08-12 10:57:09.548 (EP[0] sess:{i} thrd:2 user:u{} trxid:3 stmt:4 appname:app) SELECT {i}. EXECTIME: 1(ms) ROWCOUNT: 1(rows) EXEC_ID: {i}.\n",
+            i % 5
+        ));
+    }
+    let parser = LogParser::from_bytes(log.into_bytes());
+    let batches: Vec<RecordBatch> = parser.par_arrow_batches(512).map(Result::unwrap).collect();
+    assert!(batches.iter().all(|b| b.num_rows() <= 512));
+    assert_eq!(rows(&batches), 5000);
+
+    let mut ids: Vec<i64> = batches
+        .iter()
+        .flat_map(|b| b.column(13).as_primitive::<Int64Type>().values().to_vec())
+        .collect();
+    ids.sort_unstable();
+    assert_eq!(ids, (0..5000).collect::<Vec<_>>());
+}
@@ -0,0 +1,213 @@
+use dm_database_parser_sqllog::{Checkpoint, LogParser, ParseError};
+use std::io::Write;
+use tempfile::{NamedTempFile, TempDir};
+
+const LOG: &str = concat!(
+    "2025-11-17 16:09:41.123 (EP[0] sess:1 thrd:2 user:u trxid:3 stmt:4 appname:app) SELECT 1\n",
+    "2025-11-17 16:09:42.123 (EP[0] sess:2 thrd:3 user:u trxid:3 stmt:4 appname:app) SELECT\n",
+    "  2 FROM dual\n",
+    "2025-11-17 16:09:43.123 (EP[0] sess:3 thrd:4 user:u trxid:3 stmt:4 appname:app) SELECT 3\n",
+);
+
+#[test]
+fn position_and_iter_from_resume_at_same_record() {
+    let parser = LogParser::from_bytes(LOG.as_bytes());
+    let mut it = parser.iter();
+    assert_eq!(it.position(), 0);
+    it.next().unwrap().unwrap();
+    let pos = it.position();
+
+    let resumed: Vec<_> = parser
+        .iter_from(pos)
+        .unwrap()
+        .map(|r| r.unwrap().ts.to_string())
+        .collect();
+    assert_eq!(
+        resumed,
+        ["2025-11-17 16:09:42.123", "2025-11-17 16:09:43.123"]
+    );
+
+    let end = LOG.len();
+    assert_eq!(parser.iter_from(end).unwrap().count(), 0);
+}
+
+#[test]
+fn iter_from_rejects_non_boundary_offsets() {
+    let parser = LogParser::from_bytes(LOG.as_bytes());
+    assert_eq!(
+        parser.iter_from(5).err(),
+        Some(ParseError::InvalidOffset { offset: 5 })
+    );
+    // 多行记录内部的行首不是记录边界
+    let inner = LOG.find("  2 FROM").unwrap();
+    assert!(parser.iter_from(inner).is_err());
+    assert!(parser.iter_from(LOG.len() + 1).is_err());
+}
+
+#[test]
+fn checkpoint_text_round_trip() {
+    let cp = Checkpoint {
+        path: "/var/dm/log/dmsql_DM_20250812_090000.log".into(),
+        identity: Default::default(),
+        offset: 4096,
+        data_len: 8192,
+        last_ts: Some("2025-08-12 09:00:00.000".to_string()),
+    };
+    let parsed: Checkpoint = cp.to_string().parse().unwrap();
+    assert_eq!(parsed, cp);
+
+    // 路径中的换行、回车与反斜杠不能破坏逐行格式
+    let odd = Checkpoint {
+        path: "/tmp/a\nb\\c\rd=e.log".into(),
+        ..cp.clone()
+    };
+    let text = odd.to_string();
+    assert_eq!(text.lines().count(), 6);
+    assert_eq!(text.parse::<Checkpoint>().unwrap(), odd);
+    assert!(
+        "path=/x\\q\noffset=1\ndata_len=1"
+            .parse::<Checkpoint>()
+            .is_err()
+    );
+
+    assert!("offset=abc\npath=/x".parse::<Checkpoint>().is_err());
+    assert!("path=/x".parse::<Checkpoint>().is_err());
+}
+
+#[test]
+#[cfg(unix)]
+fn checkpoint_keeps_non_utf8_paths() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    let cp = Checkpoint {
+        path: OsStr::from_bytes(b"/tmp/\xff\xfe=\n.log").into(),
+        identity: Default::default(),
+        offset: 1,
+        data_len: 2,
+        last_ts: None,
+    };
+    let text = cp.to_string();
+    assert!(text.starts_with("path_hex=2f746d702ffffe3d0a2e6c6f67\n"));
+    assert_eq!(text.parse::<Checkpoint>().unwrap(), cp);
+
+    for bad in ["path_hex=2f7", "path_hex=zz", "path_hex=+f"] {
+        let text = format!("{bad}\noffset=1\ndata_len=1");
+        assert!(text.parse::<Checkpoint>().is_err(), "{bad} accepted");
+    }
+}
+
+#[test]
+#[cfg(not(miri))]
+fn checkpoint_save_load_and_resume() {
+    let mut file = NamedTempFile::new().unwrap();
+    file.write_all(LOG.as_bytes()).unwrap();
+    file.flush().unwrap();
+
+    let parser = LogParser::from_path(file.path()).unwrap();
+    let mut it = parser.iter();
+    let first = it.next().unwrap().unwrap();
+    let cp = Checkpoint::new(&parser, it.position(), Some(first.ts.to_string())).unwrap();
+    assert_eq!(cp.path, file.path());
+    assert_eq!(cp.data_len, LOG.len());
+
+    let dir = TempDir::new().unwrap();
+    let cp_path = dir.path().join("job.ckpt");
+    cp.save(&cp_path).unwrap();
+    let loaded = Checkpoint::load(&cp_path).unwrap();
+    assert_eq!(loaded, cp);
+
+    let parser = LogParser::from_path(&loaded.path).unwrap();
+    let rest = parser.iter_from_checkpoint(&loaded).unwrap().count();
+    assert_eq!(rest, 2);
+}
+
+#[test]
+#[cfg(all(unix, not(miri)))]
+fn checkpoint_detects_replaced_file() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("sqllog.txt");
+    std::fs::write(&path, LOG).unwrap();
+    let cp = Checkpoint::new(&LogParser::from_path(&path).unwrap(), 0, None).unwrap();
+
+    // 以新文件替换（inode 变化）
+    let replacement = dir.path().join("new.txt");
+    std::fs::write(&replacement, LOG).unwrap();
+    std::fs::rename(&replacement, &path).unwrap();
+
+    let parser = LogParser::from_path(&path).unwrap();
+    assert!(matches!(
+        cp.validate(&parser),
+        Err(ParseError::CheckpointMismatch { .. })
+    ));
+}
+
+#[test]
+#[cfg(not(miri))]
+fn checkpoint_rejects_other_file_and_truncation() {
+    let dir = TempDir::new().unwrap();
+    let a = dir.path().join("a.log");
+    let b = dir.path().join("b.log");
+    std::fs::write(&a, LOG).unwrap();
+    std::fs::write(&b, LOG).unwrap();
+
+    let parser_a = LogParser::from_path(&a).unwrap();
+    let mut it = parser_a.iter();
+    it.next();
+    let cp = Checkpoint::new(&parser_a, it.position(), None).unwrap();
+
+    // 另一个文件，即使内容相同也不能套用检查点
+    let parser_b = LogParser::from_path(&b).unwrap();
+    assert!(matches!(
+        parser_b.iter_from_checkpoint(&cp),
+        Err(ParseError::CheckpointMismatch { .. })
+    ));
+    // 同一文件经不同写法的路径打开仍视为匹配
+    let relative = dir.path().join(".").join("a.log");
+    let parser_a2 = LogParser::from_path(&relative).unwrap();
+    assert_eq!(parser_a2.iter_from_checkpoint(&cp).unwrap().count(), 2);
+
+    // 内存中构建的解析器没有路径
+    let in_memory = LogParser::from_bytes(LOG.as_bytes());
+    assert!(in_memory.iter_from_checkpoint(&cp).is_err());
+    assert_eq!(
+        Checkpoint::new(&in_memory, 0, None),
+        Err(ParseError::NoSourceFile)
+    );
+
+    // 原地截断（inode 不变）
+    let truncated = &LOG[..LOG.len() - 10];
+    std::fs::OpenOptions::new()
+        .write(true)
+        .truncate(true)
+        .open(&a)
+        .unwrap()
+        .write_all(truncated.as_bytes())
+        .unwrap();
+    let parser_a3 = LogParser::from_path(&a).unwrap();
+    assert!(matches!(
+        parser_a3.iter_from_checkpoint(&cp),
+        Err(ParseError::CheckpointMismatch { .. })
+    ));
+}
+
+#[test]
+#[cfg(all(feature = "gzip", not(miri)))]
+fn checkpoint_on_compressed_input_uses_decoded_length() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("sqllog.log.gz");
+    let mut enc = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    enc.write_all(LOG.as_bytes()).unwrap();
+    std::fs::write(&path, enc.finish().unwrap()).unwrap();
+
+    let parser = LogParser::from_path(&path).unwrap();
+    let mut it = parser.iter();
+    it.next();
+    it.next();
+    // 偏移已超过压缩文件在磁盘上的大小
+    assert!(it.position() as u64 > std::fs::metadata(&path).unwrap().len());
+    let cp = Checkpoint::new(&parser, it.position(), None).unwrap();
+
+    let reopened = LogParser::from_path(&path).unwrap();
+    assert_eq!(reopened.iter_from_checkpoint(&cp).unwrap().count(), 1);
+}
@@ -0,0 +1,117 @@
+use dm_database_parser_sqllog::{ColumnBatch, Encoding, LogParser};
+use encoding::all::GB18030;
+use encoding::{EncoderTrap, Encoding as _};
+
+const LOG: &str = "2025-08-12 10:57:09.548 (EP[0] sess:1 thrd:2 user:alice trxid:3 stmt:4 appname:app) [SEL] SELECT 1. EXECTIME: 1.5(ms) ROWCOUNT: 1(rows) EXEC_ID: 100.\n\
+                   2025-08-12 10:57:10.000 (EP[0] sess:1 thrd:2 user:bob trxid:3 stmt:4 appname:app) [ORA]: SELECT *\n\
+                   FROM DUAL. EXECTIME: 2(ms) ROWCOUNT: 2(rows) EXEC_ID: 101.\n\
+                   2025-08-12 10:57:11.000 no meta here\n\
+                   2025-08-12 10:57:12.000 (EP[0] sess:1 thrd:2 trxid:3 stmt:4 appname:app) COMMIT\n\
+                   2025-08-12 10:57:13.000 (EP[0] sess:1 thrd:2 user:carol trxid:3 stmt:4 appname:app) [UPD] UPDATE T SET A = 1. EXECTIME: 30(ms) ROWCOUNT: 7(rows) EXEC_ID: 102.\n";
+
+#[test]
+fn columns_match_record_api() {
+    let parser = LogParser::from_bytes(LOG.as_bytes());
+    let records: Vec<_> = parser.iter().filter_map(Result::ok).collect();
+
+    let mut batches = parser.batches(3);
+    let mut rows = 0;
+    let mut errors = 0;
+    let mut sizes = Vec::new();
+    while let Some(batch) = batches.next_batch() {
+        sizes.push(batch.len());
+        errors += batch.errors().len();
+        for i in 0..batch.len() {
+            let rec = &records[rows + i];
+            let pm = rec.parse_performance_metrics();
+            assert_eq!(batch.ts_millis()[i], rec.timestamp_millis().unwrap());
+            assert_eq!(batch.username(i), rec.parse_meta().username);
+            assert_eq!(batch.exectime()[i], pm.exectime);
+            assert_eq!(batch.rowcount()[i], pm.rowcount);
+            assert_eq!(batch.exec_id()[i], pm.exec_id);
+            let body = &parser.as_bytes()[batch.body_ranges()[i].clone()];
+            assert_eq!(body, pm.sql.as_bytes());
+        }
+        assert_eq!(batch.username_offsets().len(), batch.len() + 1);
+        rows += batch.len();
+    }
+    // 第一批的第三条记录解析失败，同样占用批容量
+    assert_eq!(sizes, [2, 2]);
+    assert_eq!(rows, 4);
+    assert_eq!(errors, 1);
+    assert!(batches.next_batch().is_none());
+}
+
+#[test]
+fn fill_reuses_caller_buffer() {
+    let parser = LogParser::from_bytes(LOG.as_bytes());
+    let mut batches = parser.batches(10);
+    let mut batch = ColumnBatch::new();
+
+    assert!(batches.fill(&mut batch));
+    assert_eq!(batch.len(), 4);
+    assert_eq!(batch.username_data(), "alicebobcarol");
+    assert_eq!(batch.username(2), "");
+    assert_eq!(batch.exec_id(), [100, 101, 0, 102]);
+
+    assert!(!batches.fill(&mut batch));
+    assert!(batch.is_empty());
+    assert!(batch.errors().is_empty());
+}
+
+#[test]
+fn errors_count_toward_batch_size() {
+    let log = "2025-08-12 10:57:09.000 bad\n".repeat(5);
+    let parser = LogParser::from_bytes(log.into_bytes());
+    let mut batches = parser.batches(2);
+    let mut errors = Vec::new();
+    while let Some(batch) = batches.next_batch() {
+        assert!(batch.is_empty());
+        errors.push(batch.errors().len());
+    }
+    assert_eq!(errors, [2, 2, 1]);
+}
+
+#[test]
+fn next_batch_reuses_its_buffer() {
+    let parser = LogParser::from_bytes(LOG.as_bytes());
+    let mut batches = parser.batches(2);
+    let first = batches.next_batch().unwrap().ts_millis().as_ptr();
+    let second = batches.next_batch().unwrap().ts_millis().as_ptr();
+    assert_eq!(first, second);
+}
+
+#[test]
+fn legacy_usernames_are_decoded() {
+    let text = "2025-08-12 10:57:09.548 (EP[0] sess:1 thrd:2 user:用户 trxid:3 stmt:4 appname:app) SELECT '数据'\n";
+    let parser = LogParser::builder()
+        .encoding(Encoding::Gb18030)
+        .open_bytes(GB18030.encode(text, EncoderTrap::Strict).unwrap());
+    let mut batches = parser.batches(16);
+    let batch = batches.next_batch().unwrap();
+    assert_eq!(batch.username(0), "用户");
+    let raw = &parser.as_bytes()[batch.body_ranges()[0].clone()];
+    assert_eq!(
+        GB18030.decode(raw, encoding::DecoderTrap::Strict).unwrap(),
+        "SELECT '数据'\n"
+    );
+}
+
+#[test]
+fn record_without_body_gets_empty_range() {
+    let log = "2025-08-12 10:57:09.548 (EP[0] sess:1 thrd:2 user:alice trxid:3 stmt:4 appname:app)\n\
+               2025-08-12 10:57:10.000 (EP[0] sess:1 thrd:2 user:bob trxid:3 stmt:4 appname:app) SELECT 1";
+    let parser = LogParser::from_bytes(log.as_bytes());
+    let mut batches = parser.batches(10);
+    let batch = batches.next_batch().unwrap();
+
+    assert_eq!(batch.len(), 2);
+    let first = batch.body_ranges()[0].clone();
+    assert!(first.is_empty());
+    let first_line_end = log.find('\n').unwrap();
+    assert_eq!(first, first_line_end..first_line_end);
+    assert_eq!(
+        &parser.as_bytes()[batch.body_ranges()[1].clone()],
+        b"SELECT 1"
+    );
+}
@@ -0,0 +1,75 @@
+use dm_database_parser_sqllog::{LogParser, StreamingLogParser};
+use std::io::Write;
+use tempfile::NamedTempFile;
+
+#[cfg(any(feature = "gzip", feature = "zstd", feature = "xz"))]
+const LOG: &str = concat!(
+    "2025-11-17 16:09:41.123 (EP[0] sess:1 thrd:2 user:u trxid:3 stmt:4 appname:app) SELECT 1\n",
+    "2025-11-17 16:09:42.123 (EP[0] sess:2 thrd:3 user:u trxid:3 stmt:4 appname:app) SELECT\n",
+    "  2 FROM dual EXECTIME: 1(ms) ROWCOUNT: 1(rows) EXEC_ID: 5.\n",
+);
+
+fn write_temp(bytes: &[u8]) -> NamedTempFile {
+    let mut file = NamedTempFile::new().unwrap();
+    file.write_all(bytes).unwrap();
+    file.flush().unwrap();
+    file
+}
+
+#[cfg(any(feature = "gzip", feature = "zstd", feature = "xz"))]
+fn assert_parses(file: &NamedTempFile) {
+    let parser = LogParser::from_path(file.path()).unwrap();
+    let records: Vec<_> = parser.iter().map(|r| r.unwrap()).collect();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].body(), "SELECT 1");
+    assert_eq!(records[1].parse_indicators().unwrap().exec_id, 5);
+
+    let streamed: Vec<_> = StreamingLogParser::from_path(file.path())
+        .unwrap()
+        .map(|r| r.unwrap())
+        .collect();
+    assert_eq!(streamed.len(), 2);
+    assert_eq!(streamed[1].body(), records[1].body());
+}
+
+#[test]
+#[cfg(all(feature = "gzip", not(miri)))]
+fn gzip_archive_is_decompressed() {
+    let mut enc = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    enc.write_all(LOG.as_bytes()).unwrap();
+    assert_parses(&write_temp(&enc.finish().unwrap()));
+}
+
+#[test]
+#[cfg(all(feature = "zstd", not(miri)))]
+fn zstd_archive_is_decompressed() {
+    let compressed = zstd::encode_all(LOG.as_bytes(), 0).unwrap();
+    assert_parses(&write_temp(&compressed));
+}
+
+#[test]
+#[cfg(all(feature = "xz", not(miri)))]
+fn xz_archive_is_decompressed() {
+    let mut enc = xz2::write::XzEncoder::new(Vec::new(), 6);
+    enc.write_all(LOG.as_bytes()).unwrap();
+    assert_parses(&write_temp(&enc.finish().unwrap()));
+}
+
+#[test]
+#[cfg(all(not(feature = "gzip"), not(miri)))]
+fn gzip_without_feature_is_rejected() {
+    use dm_database_parser_sqllog::ParseError;
+
+    let file = write_temp(&[0x1F, 0x8B, 0x08, 0x00, 0x00, 0x00]);
+    let err = LogParser::from_path(file.path()).err().unwrap();
+    assert!(matches!(err, ParseError::UnsupportedCompression { ref format } if format == "gzip"));
+}
+
+#[test]
+#[cfg(not(miri))]
+fn plain_file_streams_from_path() {
+    let file = write_temp(b"2025-11-17 16:09:41.123 (EP[0] sess:1 thrd:2 user:u trxid:3 stmt:4 appname:app) SELECT 1\n");
+    let mut it = StreamingLogParser::from_path(file.path()).unwrap();
+    assert_eq!(it.next().unwrap().unwrap().ts, "2025-11-17 16:09:41.123");
+    assert!(it.next().is_none());
+}
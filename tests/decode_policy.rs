@@ -0,0 +1,167 @@
+use dm_database_parser_sqllog::{DecodePolicy, Encoding, LogParser, ParseError};
+use encoding::all::GB18030;
+use encoding::{EncoderTrap, Encoding as _};
+
+const GOOD: &str = "2025-11-17 16:09:41.123 (EP[0] sess:1 thrd:2 user:用户 trxid:3 stmt:4 appname:app) [SEL] SELECT '数据'\n";
+
+/// 一条合法 GB18030 记录，后接一条正文含非法字节 0xFF 的记录；返回数据与 0xFF 的偏移
+fn log_with_bad_body() -> (Vec<u8>, usize) {
+    let mut data = GB18030.encode(GOOD, EncoderTrap::Strict).unwrap();
+    data.extend_from_slice(
+        b"2025-11-17 16:09:42.123 (EP[0] sess:2 thrd:2 user:u trxid:3 stmt:4 appname:app) SELECT '",
+    );
+    let bad_at = data.len();
+    data.extend_from_slice(b"\xFF'\n");
+    (data, bad_at)
+}
+
+fn parser(data: Vec<u8>, policy: DecodePolicy) -> LogParser {
+    LogParser::builder()
+        .encoding(Encoding::Gb18030)
+        .decode_policy(policy)
+        .open_bytes(data)
+}
+
+#[test]
+fn lossy_default_does_not_check_body() {
+    let (data, _) = log_with_bad_body();
+    let parser = parser(data, DecodePolicy::Lossy);
+    let mut iter = parser.iter();
+    let records: Vec<_> = iter.by_ref().map(|r| r.unwrap()).collect();
+    assert_eq!(records.len(), 2);
+    assert!(records.iter().all(|r| !r.is_lossy()));
+    assert!(records[1].body().contains('\u{FFFD}'));
+    assert_eq!(iter.decode_stats().lossy, 0);
+}
+
+#[test]
+fn lossy_meta_is_always_flagged() {
+    let mut data = b"2025-11-17 16:09:41.123 (EP[0] sess:1 thrd:2 user:\xFF trxid:3 stmt:4 appname:app) SELECT 1\n".to_vec();
+    data.extend_from_slice(&GB18030.encode(GOOD, EncoderTrap::Strict).unwrap());
+    let parser = parser(data, DecodePolicy::Lossy);
+    let mut iter = parser.iter();
+    let lossy: Vec<bool> = iter.by_ref().map(|r| r.unwrap().is_lossy()).collect();
+    assert_eq!(lossy, [true, false]);
+    assert_eq!(iter.decode_stats().lossy, 1);
+}
+
+#[test]
+fn report_flags_lossy_body() {
+    let (data, _) = log_with_bad_body();
+    let parser = parser(data, DecodePolicy::Report);
+    let mut iter = parser.iter();
+    let records: Vec<_> = iter.by_ref().map(|r| r.unwrap()).collect();
+    assert!(!records[0].is_lossy());
+    assert_eq!(records[0].parse_meta().username, "用户");
+    assert!(records[1].is_lossy());
+
+    let stats = iter.decode_stats();
+    assert_eq!(stats.gb18030, 2);
+    assert_eq!(stats.lossy, 1);
+}
+
+#[test]
+fn strict_reports_absolute_offset() {
+    let (data, bad_at) = log_with_bad_body();
+    let parser = parser(data, DecodePolicy::Strict);
+    let mut iter = parser.iter();
+
+    let first = iter.next().unwrap().unwrap();
+    assert!(!first.is_lossy());
+    match iter.next().unwrap() {
+        Err(ParseError::Encoding {
+            encoding, offset, ..
+        }) => {
+            assert_eq!(encoding, "GB18030");
+            assert_eq!(offset, bad_at);
+        }
+        other => panic!("expected encoding error, got {other:?}"),
+    }
+    assert!(iter.next().is_none());
+    assert_eq!(iter.decode_stats().lossy, 1);
+}
+
+#[test]
+fn strict_reports_truncated_sequence_at_end() {
+    let mut data = GB18030.encode(GOOD, EncoderTrap::Strict).unwrap();
+    data.extend_from_slice(
+        b"2025-11-17 16:09:42.123 (EP[0] sess:2 thrd:2 user:u trxid:3 stmt:4 appname:app) SELECT ",
+    );
+    let bad_at = data.len();
+    data.push(0x81);
+    let parser = parser(data, DecodePolicy::Strict);
+    let err = parser.iter().nth(1).unwrap().unwrap_err();
+    assert!(matches!(err, ParseError::Encoding { offset, .. } if offset == bad_at));
+}
+
+#[test]
+fn strict_per_record_and_par_iter() {
+    use rayon::prelude::*;
+
+    let (data, bad_at) = log_with_bad_body();
+    let parser = LogParser::builder()
+        .encoding(Encoding::PerRecord)
+        .decode_policy(DecodePolicy::Strict)
+        .open_bytes(data);
+    let errors: Vec<ParseError> = parser.par_iter().filter_map(|r| r.err()).collect();
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(errors[0], ParseError::Encoding { offset, .. } if offset == bad_at));
+}
+
+/// 约 80 KB 合法 UTF-8 记录，中部一条正文含 0xFF，之后再接合法记录，使非法字节落在采样区外
+fn utf8_log_with_bad_middle() -> (Vec<u8>, usize, usize) {
+    let good = "2025-11-17 16:09:41.123 (EP[0] sess:1 thrd:2 user:u trxid:3 stmt:4 appname:app) SELECT 1\n";
+    let mut data = Vec::new();
+    let mut count = 0;
+    while data.len() < 80 * 1024 {
+        data.extend_from_slice(good.as_bytes());
+        count += 1;
+    }
+    data.extend_from_slice(
+        b"2025-11-17 16:09:42.123 (EP[0] sess:2 thrd:2 user:u trxid:3 stmt:4 appname:app) SELECT '",
+    );
+    let bad_at = data.len();
+    data.extend_from_slice(b"\xFF'\n");
+    for _ in 0..100 {
+        data.extend_from_slice(good.as_bytes());
+    }
+    (data, bad_at, count + 101)
+}
+
+#[test]
+fn sampled_utf8_detection_still_checks_unsampled_records() {
+    let (data, bad_at, total) = utf8_log_with_bad_middle();
+
+    let strict = LogParser::builder()
+        .decode_policy(DecodePolicy::Strict)
+        .open_bytes(data.clone());
+    let errors: Vec<ParseError> = strict.iter().filter_map(|r| r.err()).collect();
+    assert_eq!(errors.len(), 1);
+    assert!(
+        matches!(&errors[0], ParseError::Encoding { encoding, offset, .. } if encoding == "UTF-8" && *offset == bad_at)
+    );
+
+    let report = LogParser::builder()
+        .decode_policy(DecodePolicy::Report)
+        .open_bytes(data.clone());
+    let mut iter = report.iter();
+    let lossy: Vec<_> = iter
+        .by_ref()
+        .map(|r| r.unwrap())
+        .filter(|r| r.is_lossy())
+        .collect();
+    assert_eq!(lossy.len(), 1);
+    assert!(lossy[0].body().contains('\u{FFFD}'));
+    let stats = iter.decode_stats();
+    assert_eq!(stats.utf8, total);
+    assert_eq!(stats.lossy, 1);
+
+    // 默认 Lossy 策略下正文按需有损替换，而不是按未校验的 UTF-8 借用
+    let lossy_parser = LogParser::from_bytes(data);
+    let bodies: Vec<String> = lossy_parser
+        .iter()
+        .map(|r| r.unwrap().body().into_owned())
+        .filter(|b| b.contains('\u{FFFD}'))
+        .collect();
+    assert_eq!(bodies, ["SELECT '\u{FFFD}'"]);
+}
@@ -0,0 +1,153 @@
+use dm_database_parser_sqllog::{Encoding, LogParser};
+
+fn ascii_record(i: usize) -> String {
+    format!(
+        "2025-11-17 16:09:41.{:03} (EP[0] sess:{i} thrd:2 user:u trxid:3 stmt:4 appname:app) SELECT {i}\n",
+        i % 1000
+    )
+}
+
+/// GB18030 记录仅出现在文件中部（头 64 KB、尾 4 KB 均为纯 ASCII）
+fn gb18030_in_middle() -> Vec<u8> {
+    let mut data = Vec::new();
+    let mut i = 0;
+    while data.len() < 80 * 1024 {
+        data.extend_from_slice(ascii_record(i).as_bytes());
+        i += 1;
+    }
+    data.extend_from_slice(b"2025-11-17 16:09:42.000 (EP[0] sess:1 thrd:2 user:");
+    data.extend_from_slice(&[0xD3, 0xC3, 0xBB, 0xA7]); // "用户"
+    data.extend_from_slice(b" trxid:3 stmt:4 appname:app) SELECT '");
+    data.extend_from_slice(&[0xD6, 0xD0, 0xCE, 0xC4]); // "中文"
+    data.extend_from_slice(b"'\n");
+    for _ in 0..100 {
+        data.extend_from_slice(ascii_record(i).as_bytes());
+        i += 1;
+    }
+    data
+}
+
+fn find_gb_record(parser: &LogParser) -> (String, String) {
+    parser
+        .iter()
+        .map(|r| r.unwrap())
+        .find(|r| r.ts == "2025-11-17 16:09:42.000")
+        .map(|r| (r.parse_meta().username.into_owned(), r.body().into_owned()))
+        .unwrap()
+}
+
+#[test]
+fn full_scan_detects_gb18030_in_middle_of_file() {
+    let parser = LogParser::builder()
+        .encoding(Encoding::AutoFullScan)
+        .open_bytes(gb18030_in_middle());
+    let (user, body) = find_gb_record(&parser);
+    assert_eq!(user, "用户");
+    assert_eq!(body, "SELECT '中文'");
+}
+
+#[test]
+fn forced_gb18030() {
+    let parser = LogParser::builder()
+        .encoding(Encoding::Gb18030)
+        .open_bytes(gb18030_in_middle());
+    let (user, body) = find_gb_record(&parser);
+    assert_eq!(user, "用户");
+    assert_eq!(body, "SELECT '中文'");
+}
+
+#[test]
+fn forced_utf8_with_invalid_bytes_stays_safe() {
+    let parser = LogParser::builder()
+        .encoding(Encoding::Utf8)
+        .open_bytes(gb18030_in_middle());
+    // 非法 UTF-8 记录不会被当作 UTF-8 借用；ASCII 记录照常解析
+    assert!(parser.iter().all(|r| r.is_ok()));
+    let (_, body) = find_gb_record(&parser);
+    assert!(body.starts_with("SELECT '"));
+}
+
+#[test]
+fn forced_utf8_decodes_meta_and_tag_lossily() {
+    // meta、tag 与正文都含 GB18030 字节（"用户" / "中文"）
+    let mut data = b"2025-11-17 16:09:41.123 (EP[0] sess:1 thrd:2 user:".to_vec();
+    data.extend_from_slice(&[0xD3, 0xC3, 0xBB, 0xA7]);
+    data.extend_from_slice(b" trxid:3 stmt:4 appname:app) [");
+    data.extend_from_slice(&[0xD6, 0xD0]);
+    data.extend_from_slice(b"] SELECT '");
+    data.extend_from_slice(&[0xD6, 0xD0, 0xCE, 0xC4]);
+    data.extend_from_slice(b"'\n");
+
+    let parser = LogParser::builder()
+        .encoding(Encoding::Utf8)
+        .open_bytes(data);
+    let mut it = parser.iter();
+    let rec = it.next().unwrap().unwrap();
+    // 不会按 GB18030 解出"用户"，而是与正文一样替换为 U+FFFD
+    let user = rec.parse_meta().username.into_owned();
+    assert_eq!(user, String::from_utf8_lossy(&[0xD3, 0xC3, 0xBB, 0xA7]));
+    assert!(user.contains('\u{FFFD}'));
+    assert!(rec.tag.as_deref().unwrap().contains('\u{FFFD}'));
+    assert!(rec.body().contains('\u{FFFD}'));
+    assert!(rec.is_lossy());
+    assert_eq!(rec.record_encoding(), Some(Encoding::Utf8));
+    assert_eq!(it.decode_stats().utf8, 1);
+    assert_eq!(it.decode_stats().gb18030, 0);
+}
+
+#[test]
+fn forced_utf8_on_valid_file() {
+    let data = "2025-11-17 16:09:41.123 (EP[0] sess:1 thrd:2 user:用户 trxid:3 stmt:4 appname:app) SELECT '中文'\n";
+    let parser = LogParser::builder()
+        .encoding(Encoding::Utf8)
+        .open_bytes(data.as_bytes());
+    let rec = parser.iter().next().unwrap().unwrap();
+    assert_eq!(rec.parse_meta().username, "用户");
+    assert_eq!(rec.body(), "SELECT '中文'\n");
+}
+
+/// CHARSET 迁移后：旧记录为 GB18030，新记录为 UTF-8
+fn mixed_charset_file() -> Vec<u8> {
+    let mut data = b"2025-11-17 16:09:41.000 (EP[0] sess:1 thrd:2 user:".to_vec();
+    data.extend_from_slice(&[0xD3, 0xC3, 0xBB, 0xA7]);
+    data.extend_from_slice(b" trxid:3 stmt:4 appname:app) SELECT '");
+    data.extend_from_slice(&[0xD6, 0xD0, 0xCE, 0xC4]);
+    data.extend_from_slice(b"'\n");
+    data.extend_from_slice(
+        "2025-11-17 16:09:42.000 (EP[0] sess:1 thrd:2 user:用户 trxid:3 stmt:4 appname:app) SELECT '中文'\n"
+            .as_bytes(),
+    );
+    data.extend_from_slice(ascii_record(3).as_bytes());
+    data
+}
+
+#[test]
+fn per_record_decodes_mixed_charset_file() {
+    let parser = LogParser::builder()
+        .encoding(Encoding::PerRecord)
+        .open_bytes(mixed_charset_file());
+    let mut it = parser.iter();
+    let records: Vec<_> = it.by_ref().map(|r| r.unwrap()).collect();
+
+    for rec in &records[..2] {
+        assert_eq!(rec.parse_meta().username, "用户");
+        assert_eq!(rec.body().trim_end(), "SELECT '中文'");
+    }
+    assert_eq!(records[0].record_encoding(), Some(Encoding::Gb18030));
+    assert_eq!(records[1].record_encoding(), Some(Encoding::Utf8));
+
+    let stats = it.decode_stats();
+    assert_eq!(stats.gb18030, 1);
+    assert_eq!(stats.utf8, 2);
+}
+
+#[test]
+fn file_level_detection_counts_all_records_one_way() {
+    let parser = LogParser::builder()
+        .encoding(Encoding::AutoFullScan)
+        .open_bytes(mixed_charset_file());
+    let mut it = parser.iter();
+    assert_eq!(it.by_ref().count(), 3);
+    let stats = it.decode_stats();
+    assert_eq!((stats.utf8, stats.gb18030), (0, 3));
+}
@@ -0,0 +1,146 @@
+use dm_database_parser_sqllog::{Column, CsvWriter, JsonLinesWriter, LogParser};
+
+const LOG: &str = "2025-08-12 10:57:09.548 (EP[1] sess:0x1 thrd:2 user:alice trxid:3 stmt:0x4 appname:disql ip:::ffff:10.0.0.1) [SEL] SELECT 'a,b'. EXECTIME: 1.5(ms) ROWCOUNT: 1(rows) EXEC_ID: 100.\n\
+                   2025-08-12 10:57:10.000 (EP[0] sess:0x1 thrd:2 user:alice trxid:3 stmt:0x5 appname:disql) [ORA]: SELECT \"X\"\n\
+                   FROM DUAL\twhere 1=1\n\
+                   2025-08-12 10:57:11.000 (EP[0] sess:0x2 thrd:3 user:bob trxid:4 stmt:0x6 appname:jdbc) COMMIT\n";
+
+fn columns() -> Vec<Column> {
+    ["ts", "user", "appname", "exectime", "sql"]
+        .iter()
+        .map(|name| name.parse().unwrap())
+        .collect()
+}
+
+#[test]
+fn csv_quotes_multiline_sql() {
+    let parser = LogParser::from_bytes(LOG.as_bytes());
+    let mut writer = CsvWriter::new(Vec::new()).columns(columns());
+    let rows = writer
+        .write_all(parser.iter().filter_map(Result::ok))
+        .unwrap();
+    assert_eq!(rows, 3);
+    let out = writer.into_inner().unwrap();
+
+    let mut reader = csv::Reader::from_reader(out.as_slice());
+    assert_eq!(
+        reader.headers().unwrap(),
+        vec!["ts", "username", "appname", "exectime", "sql"]
+    );
+    let records: Vec<csv::StringRecord> = reader.records().map(Result::unwrap).collect();
+    assert_eq!(records.len(), 3);
+    assert_eq!(&records[0][1], "alice");
+    assert_eq!(&records[0][3], "1.5");
+    assert_eq!(&records[0][4], "SELECT 'a,b'. ");
+    assert_eq!(&records[1][3], "");
+    assert_eq!(&records[1][4], "SELECT \"X\"\nFROM DUAL\twhere 1=1");
+    assert_eq!(&records[2][4], "COMMIT\n");
+}
+
+#[test]
+fn csv_without_headers_and_custom_delimiter() {
+    let parser = LogParser::from_bytes(LOG.as_bytes());
+    let mut writer = CsvWriter::new(Vec::new())
+        .columns([Column::SessId, Column::Tag, Column::ClientIp])
+        .delimiter(b'\t')
+        .unwrap()
+        .has_headers(false);
+    writer
+        .write_all(parser.iter().filter_map(Result::ok))
+        .unwrap();
+    let out = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+    assert_eq!(out, "0x1\tSEL\t::ffff:10.0.0.1\n0x1\tORA\t\n0x2\t\t\n");
+}
+
+#[test]
+fn json_lines_escape_and_null() {
+    let parser = LogParser::from_bytes(LOG.as_bytes());
+    let mut writer = JsonLinesWriter::new(Vec::new());
+    writer
+        .write_all(parser.iter().filter_map(Result::ok))
+        .unwrap();
+    let out = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+
+    let lines: Vec<serde_json::Value> = out
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[0]["exec_id"], 100);
+    assert_eq!(lines[0]["exectime"], 1.5);
+    assert_eq!(lines[0]["ep"], 1);
+    assert_eq!(lines[1]["sql"], "SELECT \"X\"\nFROM DUAL\twhere 1=1");
+    assert!(lines[1]["client_ip"].is_null());
+    assert!(lines[1]["rowcount"].is_null());
+    assert!(lines[2]["tag"].is_null());
+}
+
+#[test]
+fn par_write_matches_sequential_order() {
+    let mut log = String::from("garbage line\n");
+    for i in 0..40_000 {
+        log.push_str(&format!(
+            "2025-08-12 10:57:09.548 (EP[0] sess:{i} thrd:2 user:u{} trxid:3 stmt:4 appname:app) SELECT {i}\nFROM T. EXECTIME: 1(ms) ROWCOUNT: 1(rows) EXEC_ID: {i}.\n",
+            i % 5
+        ));
+    }
+    let parser = LogParser::from_bytes(log.into_bytes());
+
+    let mut sequential = JsonLinesWriter::new(Vec::new()).columns(columns());
+    sequential
+        .write_all(parser.iter().filter_map(Result::ok))
+        .unwrap();
+    let mut parallel = JsonLinesWriter::new(Vec::new()).columns(columns());
+    let stats = parallel.par_write(&parser).unwrap();
+    assert_eq!(stats.rows, 40_000);
+    assert_eq!(stats.errors, 1);
+    assert_eq!(
+        parallel.into_inner().unwrap(),
+        sequential.into_inner().unwrap()
+    );
+
+    let mut csv = CsvWriter::new(Vec::new());
+    csv.par_write(&parser).unwrap();
+    let out = csv.into_inner().unwrap();
+    assert_eq!(
+        csv::Reader::from_reader(out.as_slice()).records().count(),
+        40_000
+    );
+}
+
+#[test]
+fn unknown_column_is_rejected() {
+    assert!("duration".parse::<Column>().is_err());
+    assert_eq!("IP".parse::<Column>().unwrap(), Column::ClientIp);
+    assert_eq!(Column::User.to_string(), "username");
+}
+
+#[test]
+fn csv_rejects_quote_and_line_break_delimiters() {
+    for delimiter in [b'"', b'\n', b'\r'] {
+        assert!(
+            CsvWriter::new(Vec::new()).delimiter(delimiter).is_err(),
+            "delimiter {delimiter:?} accepted"
+        );
+    }
+}
+
+#[test]
+fn dropped_writers_flush_buffered_rows() {
+    let parser = LogParser::from_bytes(LOG.as_bytes());
+    let mut csv = Vec::new();
+    let mut writer = CsvWriter::new(&mut csv).columns([Column::SessId]);
+    writer
+        .write_all(parser.iter().filter_map(Result::ok))
+        .unwrap();
+    drop(writer);
+    assert_eq!(csv.iter().filter(|&&b| b == b'\n').count(), 4);
+
+    let mut json = Vec::new();
+    let mut writer = JsonLinesWriter::new(&mut json).columns([Column::SessId]);
+    writer
+        .write_all(parser.iter().filter_map(Result::ok))
+        .unwrap();
+    drop(writer);
+    assert_eq!(json.iter().filter(|&&b| b == b'\n').count(), 3);
+}
@@ -0,0 +1,70 @@
+use dm_database_parser_sqllog::{LogParser, fingerprint};
+
+#[test]
+fn literals_and_in_lists_are_collapsed() {
+    let a = fingerprint("SELECT * FROM t WHERE id = 1 AND v IN (1, 2, -3) AND s = 'x'");
+    let b = fingerprint("select *   from T\n where ID = -42 and V in (7) and S = 'it''s'");
+    assert_eq!(
+        a.text,
+        "select * from t where id = ? and v in(...) and s = ?"
+    );
+    assert_eq!(a, b);
+
+    let c = fingerprint("SELECT * FROM t WHERE id = ? AND v IN (:1, :2)");
+    assert_eq!(c.text, a.text.replace(" and s = ?", ""));
+}
+
+#[test]
+fn quoted_identifiers_and_comments() {
+    let fp = fingerprint(
+        "/* hint */ SELECT \"Name\", a.b FROM \"Sch\".\"T \"\"x\"\"\" -- trailing\nWHERE c = N'中文' OR d = q'[it's]';",
+    );
+    assert_eq!(
+        fp.text,
+        "select \"Name\", a.b from \"Sch\".\"T \"\"x\"\"\" where c = ? or d = ?"
+    );
+    assert_ne!(
+        fp,
+        fingerprint("SELECT \"NAME\", a.b FROM \"Sch\".\"T \"\"x\"\"\"")
+    );
+}
+
+#[test]
+fn q_quote_without_valid_delimiter_falls_back_to_plain_string() {
+    // 非 ASCII 定界符、或找不到 `<d>'` 时按普通字符串处理，不吞掉后续语句
+    assert_eq!(fingerprint("SELECT q'中文' FROM t").text, "select ? from t");
+    assert_eq!(fingerprint("SELECT q'[abc' FROM t").text, "select ? from t");
+    assert_eq!(
+        fingerprint("SELECT q'!it's!', q'{a}' FROM t").text,
+        "select ?, ? from t"
+    );
+}
+
+#[test]
+fn subquery_in_is_kept() {
+    let fp = fingerprint("DELETE FROM t WHERE id IN (SELECT id FROM u WHERE x > 1.5e3)");
+    assert_eq!(
+        fp.text,
+        "delete from t where id in(select id from u where x > ?)"
+    );
+    let fp = fingerprint("SELECT COUNT(*) FROM t WHERE a-1 > 0");
+    assert_eq!(fp.text, "select count(*) from t where a - ? > ?");
+}
+
+#[test]
+fn hash_is_stable() {
+    // FNV-1a 64 位，结果可持久化，不能随版本变化
+    assert_eq!(fingerprint("").hash, 0xcbf2_9ce4_8422_2325);
+    assert_eq!(fingerprint("SELECT 1").hash, fingerprint("select 2;").hash);
+    assert_ne!(fingerprint("SELECT 1").hash, fingerprint("SELECT a").hash);
+}
+
+#[test]
+fn record_fingerprint_uses_sql_body() {
+    let log = "2025-08-12 10:57:09.548 (EP[0] sess:1 thrd:2 user:a trxid:3 stmt:4 appname:app) [SEL] SELECT * FROM t WHERE id = 1. EXECTIME: 1.5(ms) ROWCOUNT: 1(rows) EXEC_ID: 100.\n\
+               2025-08-12 10:57:10.000 (EP[0] sess:1 thrd:2 user:a trxid:3 stmt:5 appname:app) [ORA]: SELECT *\n  FROM t\n  WHERE id = 2\n";
+    let parser = LogParser::from_bytes(log.as_bytes());
+    let fps: Vec<_> = parser.iter().map(|r| r.unwrap().fingerprint()).collect();
+    assert_eq!(fps[0].text, "select * from t where id = ?");
+    assert_eq!(fps[0], fps[1]);
+}
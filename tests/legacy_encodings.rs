@@ -0,0 +1,86 @@
+use dm_database_parser_sqllog::{Encoding, LogParser};
+use encoding::all::{BIG5_2003, GBK, UTF_16BE, UTF_16LE};
+use encoding::{EncoderTrap, EncodingRef};
+
+fn encode_log(codec: EncodingRef, user: &str, sql: &str) -> Vec<u8> {
+    let text = format!(
+        "2025-11-17 16:09:41.123 (EP[0] sess:1 thrd:2 user:{user} trxid:3 stmt:4 appname:app) [SEL] SELECT * FROM T WHERE C = '{sql}'\n\
+         2025-11-17 16:09:42.123 (EP[0] sess:2 thrd:2 user:{user} trxid:3 stmt:4 appname:app) SELECT 2\n"
+    );
+    codec.encode(&text, EncoderTrap::Strict).unwrap()
+}
+
+fn first_user_and_body(parser: &LogParser) -> (String, String) {
+    let rec = parser.iter().next().unwrap().unwrap();
+    (
+        rec.parse_meta().username.into_owned(),
+        rec.body().trim_end().to_string(),
+    )
+}
+
+#[test]
+fn forced_big5() {
+    let parser = LogParser::builder()
+        .encoding(Encoding::Big5)
+        .open_bytes(encode_log(BIG5_2003, "用戶", "資料庫"));
+    let (user, body) = first_user_and_body(&parser);
+    assert_eq!(user, "用戶");
+    assert_eq!(body, "SELECT * FROM T WHERE C = '資料庫'");
+}
+
+#[test]
+fn forced_gbk_and_gb2312() {
+    for enc in [Encoding::Gbk, Encoding::Gb2312] {
+        let parser = LogParser::builder()
+            .encoding(enc)
+            .open_bytes(encode_log(GBK, "用户", "数据"));
+        assert_eq!(
+            first_user_and_body(&parser),
+            (
+                "用户".to_string(),
+                "SELECT * FROM T WHERE C = '数据'".to_string()
+            )
+        );
+        assert_eq!(
+            parser.iter().next().unwrap().unwrap().record_encoding(),
+            Some(Encoding::Gbk)
+        );
+    }
+}
+
+#[test]
+fn auto_detection_picks_between_candidates() {
+    let candidates = [Encoding::Gbk, Encoding::Big5];
+
+    let big5 = LogParser::builder()
+        .candidate_encodings(candidates)
+        .open_bytes(encode_log(BIG5_2003, "用戶名稱", "中文資料的測試"));
+    assert_eq!(first_user_and_body(&big5).0, "用戶名稱");
+
+    let gbk = LogParser::builder()
+        .candidate_encodings(candidates)
+        .open_bytes(encode_log(GBK, "用户名称", "中文数据的测试"));
+    assert_eq!(first_user_and_body(&gbk).0, "用户名称");
+}
+
+#[test]
+fn utf16_is_transcoded() {
+    for codec in [UTF_16LE as EncodingRef, UTF_16BE] {
+        let parser = LogParser::from_bytes(encode_log(codec, "用户", "数据"));
+        let (user, body) = first_user_and_body(&parser);
+        assert_eq!(user, "用户");
+        assert_eq!(body, "SELECT * FROM T WHERE C = '数据'");
+        assert_eq!(parser.iter().count(), 2);
+    }
+
+    // 带 BOM 的 UTF-16LE，强制指定编码
+    let mut data = vec![0xFF, 0xFE];
+    data.extend(encode_log(UTF_16LE, "u", "x"));
+    let parser = LogParser::builder()
+        .encoding(Encoding::Utf16Le)
+        .open_bytes(data);
+    assert_eq!(
+        parser.iter().next().unwrap().unwrap().ts,
+        "2025-11-17 16:09:41.123"
+    );
+}
@@ -0,0 +1,239 @@
+use dm_database_parser_sqllog::{
+    Encoding, LogFollower, LogParser, LogParserBuilder, ParseError, SqllogTag,
+};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::time::Duration;
+use tempfile::TempDir;
+
+fn record(sec: u32, body: &str) -> String {
+    format!(
+        "2025-08-12 10:00:{sec:02}.000 (EP[0] sess:1 thrd:2 user:U trxid:3 stmt:4 appname:app) {body}\n"
+    )
+}
+
+fn append(path: &Path, text: &str) {
+    let mut f = OpenOptions::new().append(true).open(path).unwrap();
+    f.write_all(text.as_bytes()).unwrap();
+}
+
+#[test]
+#[cfg(not(miri))]
+fn follower_holds_last_record_until_next_timestamp() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("dmsql_DMSERVER_20250812_100000.log");
+    File::create(&path).unwrap();
+
+    let mut follower = LogFollower::open(&path)
+        .unwrap()
+        .idle_timeout(Duration::from_secs(3600));
+    assert!(follower.poll_next().is_none());
+
+    append(&path, &record(1, "SELECT 1"));
+    // 仅有一条记录，无法确认其已写完
+    assert!(follower.poll_next().is_none());
+
+    // 多行记录的第一部分到达后，第一条记录才被确认
+    append(
+        &path,
+        "2025-08-12 10:00:02.000 (EP[0] sess:1 thrd:2 user:U trxid:3 stmt:4 appname:app) SELECT\n",
+    );
+    let r1 = follower.poll_next().unwrap().unwrap();
+    assert_eq!(r1.body(), "SELECT 1");
+    assert!(follower.poll_next().is_none());
+
+    append(&path, "  2 FROM dual\n");
+    append(&path, &record(3, "SELECT 3"));
+    let r2 = follower.poll_next().unwrap().unwrap();
+    assert_eq!(r2.ts, "2025-08-12 10:00:02.000");
+    assert_eq!(r2.body(), "SELECT\n  2 FROM dual");
+    assert!(follower.poll_next().is_none());
+}
+
+#[test]
+#[cfg(not(miri))]
+fn follower_flushes_after_idle_timeout() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("sqllog.txt");
+    std::fs::write(&path, record(1, "SELECT 1")).unwrap();
+
+    let mut follower = LogFollower::open(&path)
+        .unwrap()
+        .idle_timeout(Duration::ZERO);
+    let r = follower.poll_next().unwrap().unwrap();
+    assert_eq!(r.body(), "SELECT 1\n");
+    assert!(follower.poll_next().is_none());
+}
+
+#[test]
+#[cfg(not(miri))]
+fn follower_switches_to_rotated_file() {
+    let dir = TempDir::new().unwrap();
+    let first = dir.path().join("dmsql_DMSERVER_20250812_100000.log");
+    let second = dir.path().join("dmsql_DMSERVER_20250812_110000.log");
+    let other_instance = dir.path().join("dmsql_OTHER_20250812_103000.log");
+    std::fs::write(&first, format!("{}{}", record(1, "A"), record(2, "B"))).unwrap();
+    std::fs::write(&other_instance, record(9, "IGNORED")).unwrap();
+
+    let mut follower = LogFollower::open(&first)
+        .unwrap()
+        .idle_timeout(Duration::from_secs(3600))
+        .rotation_check_interval(Duration::ZERO);
+    assert_eq!(follower.poll_next().unwrap().unwrap().body(), "A");
+    assert!(follower.poll_next().is_none());
+
+    // DM 切换到新文件：旧文件最后一条记录被确认并产出，然后跟踪新文件
+    std::fs::write(&second, format!("{}{}", record(3, "C"), record(4, "D"))).unwrap();
+    assert_eq!(follower.poll_next().unwrap().unwrap().body(), "B\n");
+    assert_eq!(follower.poll_next().unwrap().unwrap().body(), "C");
+    assert_eq!(follower.path(), second.as_path());
+    assert!(follower.poll_next().is_none());
+}
+
+#[test]
+#[cfg(not(miri))]
+fn follower_restarts_after_truncation() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("sqllog.txt");
+    std::fs::write(&path, format!("{}{}", record(1, "A"), record(2, "B"))).unwrap();
+
+    let mut follower = LogFollower::open(&path)
+        .unwrap()
+        .idle_timeout(Duration::from_secs(3600));
+    assert_eq!(follower.poll_next().unwrap().unwrap().body(), "A");
+
+    std::fs::write(&path, record(5, "X")).unwrap();
+    assert!(follower.poll_next().is_none());
+    append(&path, &record(6, "Y"));
+    assert_eq!(follower.poll_next().unwrap().unwrap().body(), "X");
+    assert_eq!(follower.offset(), std::fs::metadata(&path).unwrap().len());
+}
+
+#[test]
+#[cfg(all(unix, not(miri)))]
+fn follower_reopens_path_after_rename_and_recreate() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("sqllog.txt");
+    std::fs::write(&path, format!("{}{}", record(1, "A"), record(2, "B"))).unwrap();
+
+    let mut follower = LogFollower::open(&path)
+        .unwrap()
+        .idle_timeout(Duration::from_secs(3600));
+    assert_eq!(follower.poll_next().unwrap().unwrap().body(), "A");
+
+    // logrotate：改名后原文件仍可能被追加，随后新建同名文件（比原文件更长，长度检查无法发现）
+    let rotated = dir.path().join("sqllog.txt.1");
+    std::fs::rename(&path, &rotated).unwrap();
+    append(&rotated, &record(3, "C"));
+    let fresh: String = (10..20).map(|sec| record(sec, "NEW")).collect();
+    std::fs::write(&path, fresh).unwrap();
+
+    assert_eq!(follower.poll_next().unwrap().unwrap().body(), "B");
+    assert_eq!(follower.poll_next().unwrap().unwrap().body(), "C\n");
+    let next = follower.poll_next().unwrap().unwrap();
+    assert_eq!(next.ts, "2025-08-12 10:00:10.000");
+    assert_eq!(follower.path(), path.as_path());
+}
+
+#[test]
+#[cfg(not(miri))]
+fn follower_drops_continuation_of_flushed_record() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("sqllog.txt");
+    std::fs::write(
+        &path,
+        "2025-08-12 10:00:01.000 (EP[0] sess:1 thrd:2 user:U trxid:3 stmt:4 appname:app) SELECT\n",
+    )
+    .unwrap();
+
+    let mut follower = LogFollower::open(&path)
+        .unwrap()
+        .idle_timeout(Duration::ZERO);
+    assert_eq!(follower.poll_next().unwrap().unwrap().body(), "SELECT\n");
+
+    // 超时产出后才到达的续行不能成为一条独立的（错误）记录
+    append(&path, "  1 FROM dual\n  WHERE 1 = 1\n");
+    assert!(follower.poll_next().is_none());
+
+    append(&path, &record(2, "SELECT 2"));
+    assert_eq!(follower.poll_next().unwrap().unwrap().body(), "SELECT 2\n");
+    assert!(follower.poll_next().is_none());
+}
+
+#[test]
+#[cfg(not(miri))]
+fn follower_open_at_validates_offset() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("sqllog.txt");
+    let first = record(1, "A");
+    std::fs::write(&path, format!("{first}{}", record(2, "B"))).unwrap();
+    let len = std::fs::metadata(&path).unwrap().len();
+
+    let mut follower = LogFollower::open_at(&path, first.len() as u64)
+        .unwrap()
+        .idle_timeout(Duration::ZERO);
+    assert_eq!(follower.poll_next().unwrap().unwrap().body(), "B\n");
+    assert!(LogFollower::open_at(&path, len).is_ok());
+
+    for bad in [5, first.len() as u64 - 1, len + 1] {
+        assert!(matches!(
+            LogFollower::open_at(&path, bad),
+            Err(ParseError::InvalidOffset { offset }) if offset == bad as usize
+        ));
+    }
+}
+
+#[test]
+#[cfg(not(miri))]
+fn follower_throttles_rotation_checks() {
+    let dir = TempDir::new().unwrap();
+    let first = dir.path().join("dmsql_DMSERVER_20250812_100000.log");
+    let second = dir.path().join("dmsql_DMSERVER_20250812_110000.log");
+    std::fs::write(&first, format!("{}{}", record(1, "A"), record(2, "B"))).unwrap();
+
+    let mut follower = LogFollower::open(&first)
+        .unwrap()
+        .idle_timeout(Duration::from_secs(3600))
+        .rotation_check_interval(Duration::from_secs(3600));
+    assert_eq!(follower.poll_next().unwrap().unwrap().body(), "A");
+    assert!(follower.poll_next().is_none());
+
+    // 上次扫描目录后未超过间隔，不会发现新文件
+    std::fs::write(&second, record(3, "C")).unwrap();
+    assert!(follower.poll_next().is_none());
+    assert_eq!(follower.path(), first.as_path());
+}
+
+/// 用户名为 Big5 编码的"中文"，正文以非标准标记 `[T1]` 开头
+fn big5_record() -> Vec<u8> {
+    let mut data = b"2025-08-12 10:00:01.000 (EP[0] sess:1 thrd:2 user:".to_vec();
+    data.extend_from_slice(&[0xA4, 0xA4, 0xA4, 0xE5]);
+    data.extend_from_slice(b" trxid:3 stmt:4 appname:app) [T1] SELECT 1\n");
+    data
+}
+
+fn big5_builder() -> LogParserBuilder {
+    LogParser::builder()
+        .encoding(Encoding::Big5)
+        .utc_offset(8 * 3600)
+        .recognized_tags([SqllogTag::Sel])
+}
+
+#[test]
+#[cfg(not(miri))]
+fn follower_applies_builder_options() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("sqllog.txt");
+    std::fs::write(&path, big5_record()).unwrap();
+
+    let mut follower = LogFollower::open(&path)
+        .unwrap()
+        .idle_timeout(Duration::ZERO)
+        .options(big5_builder());
+    let rec = follower.poll_next().unwrap().unwrap();
+    assert_eq!(rec.parse_meta().username, "中文");
+    assert_eq!(rec.utc_offset(), 8 * 3600);
+    assert_eq!(rec.tag, None);
+    assert_eq!(rec.body(), "[T1] SELECT 1\n");
+}
@@ -0,0 +1,292 @@
+use dm_database_parser_sqllog::{
+    Encoding, LogParser, LogParserBuilder, LogSet, ParseError, SqllogTag,
+};
+use rayon::prelude::*;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn record(ts: &str, body: &str) -> String {
+    format!("{ts} (EP[0] sess:1 thrd:2 user:U trxid:3 stmt:4 appname:app) {body}\n")
+}
+
+fn write(dir: &Path, name: &str, content: &str) {
+    std::fs::write(dir.join(name), content).unwrap();
+}
+
+fn file_names(set: &LogSet) -> Vec<String> {
+    set.paths()
+        .map(|p| p.file_name().unwrap().to_str().unwrap().to_string())
+        .collect()
+}
+
+#[test]
+#[cfg(not(miri))]
+fn from_dir_orders_by_dm_file_name_then_first_ts() {
+    let dir = TempDir::new().unwrap();
+    write(
+        dir.path(),
+        "dmsql_DM_20250812_120000.log",
+        &record("2025-08-12 12:00:00.000", "C"),
+    );
+    write(
+        dir.path(),
+        "dmsql_DM_20250812_090000.log",
+        &record("2025-08-12 09:00:00.000", "A"),
+    );
+    // 非 DM 命名的文件，按首条记录时间排序
+    write(
+        dir.path(),
+        "manual_export.log",
+        &record("2025-08-12 10:30:00.000", "B"),
+    );
+    write(dir.path(), "notes.txt", "not a log");
+
+    let set = LogSet::from_dir(dir.path()).unwrap();
+    assert_eq!(
+        file_names(&set),
+        [
+            "dmsql_DM_20250812_090000.log",
+            "manual_export.log",
+            "dmsql_DM_20250812_120000.log"
+        ]
+    );
+
+    let bodies: Vec<_> = set
+        .iter()
+        .map(|(_, r)| r.unwrap().body().trim_end().to_string())
+        .collect();
+    assert_eq!(bodies, ["A", "B", "C"]);
+}
+
+#[test]
+#[cfg(not(miri))]
+fn from_glob_filters_by_file_name() {
+    let dir = TempDir::new().unwrap();
+    write(
+        dir.path(),
+        "dmsql_DM_20250812_090000.log",
+        &record("2025-08-12 09:00:00.000", "A"),
+    );
+    write(
+        dir.path(),
+        "dmsql_DM_20250813_090000.log",
+        &record("2025-08-13 09:00:00.000", "B"),
+    );
+
+    let pattern = dir.path().join("dmsql_DM_20250812_*.log");
+    let set = LogSet::from_glob(pattern.to_str().unwrap()).unwrap();
+    assert_eq!(file_names(&set), ["dmsql_DM_20250812_090000.log"]);
+}
+
+#[test]
+#[cfg(not(miri))]
+fn records_and_errors_carry_source_path() {
+    let dir = TempDir::new().unwrap();
+    write(
+        dir.path(),
+        "dmsql_DM_20250812_090000.log",
+        &format!(
+            "{}{}",
+            record("2025-08-12 09:00:00.000", "A"),
+            record("2025-08-12 09:00:01.000", "B")
+        ),
+    );
+    write(
+        dir.path(),
+        "dmsql_DM_20250812_100000.log",
+        &format!("garbage line\n{}", record("2025-08-12 10:00:00.000", "C")),
+    );
+
+    let set = LogSet::from_dir(dir.path()).unwrap();
+    let errors: Vec<_> = set.iter().filter(|(_, r)| r.is_err()).collect();
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].0.ends_with("dmsql_DM_20250812_100000.log"));
+
+    let mut seq: Vec<_> = set
+        .iter()
+        .filter_map(|(p, r)| r.ok().map(|r| (p.to_path_buf(), r.ts.to_string())))
+        .collect();
+    let mut par: Vec<_> = set
+        .par_iter()
+        .filter_map(|(p, r)| r.ok().map(|r| (p.to_path_buf(), r.ts.to_string())))
+        .collect();
+    seq.sort();
+    par.sort();
+    assert_eq!(seq.len(), 3);
+    assert_eq!(seq, par);
+}
+
+#[test]
+#[cfg(not(miri))]
+fn empty_dir_yields_empty_set() {
+    let dir = TempDir::new().unwrap();
+    let set = LogSet::from_dir(dir.path()).unwrap();
+    assert!(set.is_empty());
+    assert_eq!(set.iter().count(), 0);
+}
+
+#[test]
+#[cfg(not(miri))]
+fn files_are_opened_lazily() {
+    let dir = TempDir::new().unwrap();
+    write(
+        dir.path(),
+        "dmsql_DM_20250812_090000.log",
+        &record("2025-08-12 09:00:00.000", "A"),
+    );
+    let missing = dir.path().join("dmsql_DM_20250812_100000.log");
+
+    // 构建时不打开按文件名排序的文件，缺失的文件在迭代到它时才报错
+    let set =
+        LogSet::from_paths([dir.path().join("dmsql_DM_20250812_090000.log"), missing]).unwrap();
+    assert_eq!(set.len(), 2);
+
+    let items: Vec<_> = set.iter().collect();
+    assert_eq!(items.len(), 2);
+    assert_eq!(items[0].1.as_ref().unwrap().body(), "A\n");
+    assert!(items[1].0.ends_with("dmsql_DM_20250812_100000.log"));
+    assert!(matches!(items[1].1, Err(ParseError::IoError(_))));
+
+    let opened: Vec<_> = set.parsers().map(|(_, p)| p.is_ok()).collect();
+    assert_eq!(opened, [true, false]);
+}
+
+/// 用户名为 Big5 编码的"中文"，正文以非标准标记 `[T1]` 开头
+fn big5_record() -> Vec<u8> {
+    let mut data = b"2025-08-12 10:00:01.000 (EP[0] sess:1 thrd:2 user:".to_vec();
+    data.extend_from_slice(&[0xA4, 0xA4, 0xA4, 0xE5]);
+    data.extend_from_slice(b" trxid:3 stmt:4 appname:app) [T1] SELECT 1\n");
+    data
+}
+
+fn big5_builder() -> LogParserBuilder {
+    LogParser::builder()
+        .encoding(Encoding::Big5)
+        .utc_offset(8 * 3600)
+        .recognized_tags([SqllogTag::Sel])
+}
+
+#[test]
+#[cfg(not(miri))]
+fn log_set_applies_builder_options() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("dmsql_DM_20250812_100000.log"),
+        big5_record(),
+    )
+    .unwrap();
+
+    let set = LogSet::from_dir(dir.path())
+        .unwrap()
+        .options(big5_builder());
+    let check = |rec: &dm_database_parser_sqllog::Sqllog<'_>| {
+        assert_eq!(rec.parse_meta().username, "中文");
+        assert_eq!(rec.utc_offset(), 8 * 3600);
+        assert_eq!(rec.timestamp_millis(), Some(1_754_964_001_000));
+        assert_eq!(rec.tag, None);
+        assert_eq!(rec.body(), "[T1] SELECT 1\n");
+    };
+
+    let records: Vec<_> = set.iter().map(|(_, r)| r.unwrap()).collect();
+    assert_eq!(records.len(), 1);
+    check(&records[0]);
+    set.par_iter().for_each(|(_, r)| check(&r.unwrap()));
+    for (_, parser) in set.parsers() {
+        check(&parser.unwrap().iter().next().unwrap().unwrap());
+    }
+}
+
+#[test]
+#[cfg(not(miri))]
+fn unopenable_unnamed_file_fails_during_iteration() {
+    let dir = TempDir::new().unwrap();
+    write(
+        dir.path(),
+        "dmsql_DM_20250812_090000.log",
+        &record("2025-08-12 09:00:00.000", "A"),
+    );
+    // 文件名不含时间，构建时需要读取首条记录；打开失败不应使整个集合构建失败
+    let missing = dir.path().join("manual_export.log");
+
+    let set = LogSet::from_paths([
+        missing.clone(),
+        dir.path().join("dmsql_DM_20250812_090000.log"),
+    ])
+    .unwrap();
+    assert_eq!(set.len(), 2);
+
+    let items: Vec<_> = set.iter().collect();
+    assert_eq!(items.len(), 2);
+    assert_eq!(items[0].1.as_ref().unwrap().body(), "A\n");
+    assert_eq!(items[1].0, missing.as_path());
+    assert!(matches!(items[1].1, Err(ParseError::IoError(_))));
+    assert_eq!(set.par_iter().filter(|(_, r)| r.is_err()).count(), 1);
+}
+
+#[test]
+#[cfg(not(miri))]
+fn par_iter_splits_large_files() {
+    let dir = TempDir::new().unwrap();
+    let line = record("2025-08-12 09:00:00.000", "SELECT 1");
+    let count = (33 * 1024 * 1024) / line.len() + 1;
+    let mut content = String::with_capacity(count * line.len() + 64);
+    content.push_str("garbage line\n");
+    for _ in 0..count {
+        content.push_str(&line);
+    }
+    write(dir.path(), "dmsql_DM_20250812_090000.log", &content);
+    write(
+        dir.path(),
+        "dmsql_DM_20250812_100000.log",
+        &record("2025-08-12 10:00:00.000", "B"),
+    );
+
+    let set = LogSet::from_dir(dir.path()).unwrap();
+    let (ok, err): (Vec<_>, Vec<_>) = set.par_iter().partition(|(_, r)| r.is_ok());
+    assert_eq!(ok.len(), count + 1);
+    assert_eq!(err.len(), 1);
+    assert!(err[0].0.ends_with("dmsql_DM_20250812_090000.log"));
+    assert_eq!(set.iter().count(), count + 2);
+}
+
+#[test]
+#[cfg(all(unix, not(miri)))]
+fn from_dir_follows_symlinks() {
+    let dir = TempDir::new().unwrap();
+    let target = TempDir::new().unwrap();
+    write(
+        target.path(),
+        "dmsql_DM_20250812_090000.log",
+        &record("2025-08-12 09:00:00.000", "A"),
+    );
+    std::os::unix::fs::symlink(
+        target.path().join("dmsql_DM_20250812_090000.log"),
+        dir.path().join("dmsql_DM_20250812_090000.log"),
+    )
+    .unwrap();
+    // 指向目录的链接不是文件
+    std::os::unix::fs::symlink(target.path(), dir.path().join("linked_dir.log")).unwrap();
+
+    let set = LogSet::from_dir(dir.path()).unwrap();
+    assert_eq!(file_names(&set), ["dmsql_DM_20250812_090000.log"]);
+    assert_eq!(set.iter().count(), 1);
+}
+
+#[test]
+#[cfg(not(miri))]
+fn first_record_probe_reads_only_the_file_head() {
+    let dir = TempDir::new().unwrap();
+    // 开头超过探测范围的非记录内容：排序时视为没有时间
+    let mut late = "x".repeat(100 * 1024);
+    late.push('\n');
+    late.push_str(&record("2025-08-12 08:00:00.000", "late"));
+    write(dir.path(), "a_padded.log", &late);
+    write(
+        dir.path(),
+        "b_plain.log",
+        &record("2025-08-12 10:00:00.000", "plain"),
+    );
+
+    let set = LogSet::from_dir(dir.path()).unwrap();
+    assert_eq!(file_names(&set), ["b_plain.log", "a_padded.log"]);
+}
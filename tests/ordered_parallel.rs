@@ -0,0 +1,121 @@
+use std::borrow::Cow;
+use std::time::Duration;
+
+use dm_database_parser_sqllog::{Encoding, LogParser, RecordChunk};
+use encoding::all::GB18030;
+use encoding::{EncoderTrap, Encoding as _};
+
+fn sample_log(n: usize) -> String {
+    let mut log = String::new();
+    for i in 0..n {
+        if i % 7 == 3 {
+            log.push_str(&format!(
+                "2025-08-12 10:57:09.548 (EP[0] sess:{i} thrd:2 user:u trxid:3 stmt:4 appname:app) SELECT *\nFROM T{i}\nWHERE X = {i}\n"
+            ));
+        } else if i % 11 == 5 {
+            log.push_str("this line is not a record\n");
+        } else {
+            log.push_str(&format!(
+                "2025-08-12 10:57:09.548 (EP[0] sess:{i} thrd:2 user:u trxid:3 stmt:4 appname:app) SELECT {i}\n"
+            ));
+        }
+    }
+    log
+}
+
+#[test]
+fn par_iter_ordered_matches_sequential() {
+    let parser = LogParser::from_bytes(sample_log(5_000).into_bytes());
+    let ordered: Vec<_> = parser.par_iter_ordered().collect();
+    let sequential: Vec<_> = parser.iter().collect();
+    assert_eq!(ordered, sequential);
+}
+
+#[test]
+fn small_chunks_preserve_order_and_boundaries() {
+    let parser = LogParser::from_bytes(sample_log(2_000).into_bytes());
+    let expected: Vec<_> = parser.iter().collect();
+
+    let chunks: Vec<_> = parser.par_chunks().chunk_bytes(500).window(3).collect();
+    assert!(chunks.len() > 100);
+    for (i, chunk) in chunks.iter().enumerate() {
+        assert_eq!(chunk.index, i);
+        assert!(!chunk.records.is_empty());
+    }
+    assert!(chunks.windows(2).all(|w| w[0].offset < w[1].offset));
+
+    let flattened: Vec<_> = chunks.into_iter().flat_map(|c| c.records).collect();
+    assert_eq!(flattened, expected);
+}
+
+#[test]
+fn empty_input_yields_no_chunks() {
+    let parser = LogParser::from_bytes(Vec::new());
+    assert_eq!(parser.par_chunks().count(), 0);
+    assert_eq!(parser.par_iter_ordered().count(), 0);
+}
+
+#[test]
+fn prefetch_works_on_single_thread_pool_and_early_drop() {
+    let parser = LogParser::from_bytes(sample_log(2_000).into_bytes());
+    let expected: Vec<_> = parser.iter().collect();
+
+    // 在唯一的 Rayon 工作线程上迭代：后台窗口无人调度时由迭代器自行解析，不会死锁
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(1)
+        .build()
+        .unwrap();
+    let flattened: Vec<_> = pool.install(|| {
+        parser
+            .par_chunks()
+            .chunk_bytes(500)
+            .window(2)
+            .flat_map(|c| c.records)
+            .collect()
+    });
+    assert_eq!(flattened, expected);
+
+    // 后台窗口尚在解析时丢弃迭代器
+    let mut chunks = parser.par_chunks().chunk_bytes(500).window(4);
+    assert_eq!(chunks.next().unwrap().index, 0);
+    drop(chunks);
+    drop(parser);
+}
+
+/// 消费每块后稍作停顿，让后台窗口在迭代器取结果前就已开始解析
+fn collect_slowly(parser: &LogParser) -> Vec<RecordChunk<'_>> {
+    parser
+        .par_chunks()
+        .chunk_bytes(500)
+        .window(2)
+        .inspect(|_| std::thread::sleep(Duration::from_millis(1)))
+        .collect()
+}
+
+#[test]
+fn prefetched_windows_borrow_the_parser_data() {
+    let parser = LogParser::from_bytes(sample_log(1_000).into_bytes());
+    let chunks = collect_slowly(&parser);
+    let records: Vec<_> = chunks.into_iter().flat_map(|c| c.records).collect();
+    assert_eq!(records, parser.iter().collect::<Vec<_>>());
+
+    let data = parser.as_bytes().as_ptr_range();
+    for record in records.iter().flatten() {
+        let Cow::Borrowed(content) = &record.content_raw else {
+            panic!("UTF-8 content should be borrowed");
+        };
+        assert!(data.contains(&content.as_ptr()));
+    }
+}
+
+#[test]
+fn prefetched_windows_keep_decoded_fields() {
+    let log = sample_log(1_000).replace("user:u", "user:用户");
+    let parser = LogParser::builder()
+        .encoding(Encoding::Gb18030)
+        .open_bytes(GB18030.encode(&log, EncoderTrap::Strict).unwrap());
+    let chunks = collect_slowly(&parser);
+    let records: Vec<_> = chunks.into_iter().flat_map(|c| c.records).collect();
+    assert_eq!(records, parser.iter().collect::<Vec<_>>());
+    assert_eq!(records[0].as_ref().unwrap().parse_meta().username, "用户");
+}
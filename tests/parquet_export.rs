@@ -0,0 +1,185 @@
+#![cfg(feature = "parquet")]
+
+use std::fs::File;
+
+use arrow_array::RecordBatch;
+use arrow_array::cast::AsArray;
+use arrow_array::types::Int64Type;
+use dm_database_parser_sqllog::{
+    LogParser, ParquetCompression, ParquetWriter, Partitioning, sqllog_schema,
+};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::basic::Compression;
+use parquet::file::reader::{FileReader, SerializedFileReader};
+
+fn sample_log() -> String {
+    let mut log = String::new();
+    for i in 0..300 {
+        let (day, hour) = (12 + i / 200, 10 + (i / 50) % 4);
+        log.push_str(&format!(
+            "2025-08-{day} {hour:02}:57:09.548 (EP[0] sess:{i} thrd:2 user:u{} trxid:3 stmt:4 appname:app) SELECT {i}. EXECTIME: 1(ms) ROWCOUNT: 1(rows) EXEC_ID: {i}.\n",
+            i % 3
+        ));
+    }
+    log
+}
+
+fn write(parser: &LogParser, writer: &mut ParquetWriter) {
+    for record in parser.iter() {
+        writer.write(&record.unwrap()).unwrap();
+    }
+}
+
+fn read(path: &std::path::Path) -> Vec<RecordBatch> {
+    ParquetRecordBatchReaderBuilder::try_new(File::open(path).unwrap())
+        .unwrap()
+        .build()
+        .unwrap()
+        .map(Result::unwrap)
+        .collect()
+}
+
+fn exec_ids(batches: &[RecordBatch]) -> Vec<i64> {
+    batches
+        .iter()
+        .flat_map(|b| b.column(13).as_primitive::<Int64Type>().values().to_vec())
+        .collect()
+}
+
+#[test]
+fn single_file_round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("sqllog.parquet");
+    let parser = LogParser::from_bytes(sample_log().into_bytes());
+
+    let mut writer = ParquetWriter::create(&out)
+        .row_group_size(64)
+        .compression(ParquetCompression::Zstd(3));
+    write(&parser, &mut writer);
+    assert_eq!(writer.rows(), 300);
+    assert_eq!(writer.finish().unwrap(), [out.as_path()]);
+
+    let batches = read(&out);
+    assert_eq!(batches[0].schema(), sqllog_schema());
+    assert_eq!(exec_ids(&batches), (0..300).collect::<Vec<_>>());
+
+    let reader = SerializedFileReader::new(File::open(&out).unwrap()).unwrap();
+    let meta = reader.metadata();
+    assert_eq!(meta.num_row_groups(), 5);
+    assert!(meta.row_groups().iter().all(|rg| rg.num_rows() <= 64));
+    assert!(matches!(
+        meta.row_group(0).column(0).compression(),
+        Compression::ZSTD(_)
+    ));
+}
+
+#[test]
+fn hour_partitions_use_hive_layout() {
+    let dir = tempfile::tempdir().unwrap();
+    let parser = LogParser::from_bytes(sample_log().into_bytes());
+
+    let mut writer = ParquetWriter::create(dir.path()).partition_by(Partitioning::Hour);
+    write(&parser, &mut writer);
+    let paths = writer.finish().unwrap();
+
+    let relative: Vec<_> = paths
+        .iter()
+        .map(|p| {
+            p.strip_prefix(dir.path())
+                .unwrap()
+                .to_str()
+                .unwrap()
+                .to_owned()
+        })
+        .collect();
+    assert_eq!(
+        relative,
+        [
+            "date=2025-08-12/hour=10/part-0.parquet",
+            "date=2025-08-12/hour=11/part-0.parquet",
+            "date=2025-08-12/hour=12/part-0.parquet",
+            "date=2025-08-12/hour=13/part-0.parquet",
+            "date=2025-08-13/hour=10/part-0.parquet",
+            "date=2025-08-13/hour=11/part-0.parquet",
+        ]
+    );
+
+    let first = exec_ids(&read(&paths[0]));
+    assert_eq!(first, (0..50).collect::<Vec<_>>());
+    let total: usize = paths.iter().map(|p| exec_ids(&read(p)).len()).sum();
+    assert_eq!(total, 300);
+}
+
+#[test]
+fn date_partitions_and_invalid_level() {
+    let dir = tempfile::tempdir().unwrap();
+    let parser = LogParser::from_bytes(sample_log().into_bytes());
+
+    let mut writer = ParquetWriter::create(dir.path()).partition_by(Partitioning::Date);
+    write(&parser, &mut writer);
+    let paths = writer.finish().unwrap();
+    assert_eq!(paths.len(), 2);
+    assert_eq!(exec_ids(&read(&paths[1])), (200..300).collect::<Vec<_>>());
+
+    let mut writer = ParquetWriter::create(dir.path().join("bad.parquet"))
+        .compression(ParquetCompression::Zstd(99));
+    let record = parser.iter().next().unwrap().unwrap();
+    assert!(writer.write(&record).is_err());
+}
+
+#[test]
+fn least_recent_partitions_are_closed() {
+    let dir = tempfile::tempdir().unwrap();
+    let parser = LogParser::from_bytes(sample_log().into_bytes());
+
+    // 按时间顺序写入：每个小时分区只打开一次
+    let mut writer = ParquetWriter::create(dir.path().join("ordered"))
+        .partition_by(Partitioning::Hour)
+        .max_open_partitions(1);
+    write(&parser, &mut writer);
+    let paths = writer.finish().unwrap();
+    assert_eq!(paths.len(), 6);
+    assert!(paths.iter().all(|p| p.ends_with("part-0.parquet")));
+
+    // 两天交替写入：被关闭的分区再次出现时写入新文件
+    let mut log = String::new();
+    for i in 0..4 {
+        log.push_str(&format!(
+            "2025-08-1{} 10:00:00.000 (EP[0] sess:{i} thrd:2 user:u trxid:3 stmt:4 appname:app) SELECT {i}. EXECTIME: 1(ms) ROWCOUNT: 1(rows) EXEC_ID: {i}.\n",
+            2 + i % 2
+        ));
+    }
+    let parser = LogParser::from_bytes(log.into_bytes());
+    let root = dir.path().join("interleaved");
+    let mut writer = ParquetWriter::create(&root)
+        .partition_by(Partitioning::Date)
+        .max_open_partitions(1);
+    write(&parser, &mut writer);
+    let paths = writer.finish().unwrap();
+    let expected: Vec<_> = [
+        "date=2025-08-12/part-0.parquet",
+        "date=2025-08-12/part-1.parquet",
+        "date=2025-08-13/part-0.parquet",
+        "date=2025-08-13/part-1.parquet",
+    ]
+    .iter()
+    .map(|p| root.join(p))
+    .collect();
+    assert_eq!(paths, expected);
+    let ids: Vec<_> = paths.iter().map(|p| exec_ids(&read(p))).collect();
+    assert_eq!(ids, [[0], [2], [1], [3]]);
+}
+
+#[test]
+fn zero_row_group_size_is_clamped() {
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("one.parquet");
+    let parser = LogParser::from_bytes(sample_log().into_bytes());
+    let mut writer = ParquetWriter::create(&out).row_group_size(0);
+    for record in parser.iter().take(3) {
+        writer.write(&record.unwrap()).unwrap();
+    }
+    writer.finish().unwrap();
+    let reader = SerializedFileReader::new(File::open(&out).unwrap()).unwrap();
+    assert_eq!(reader.metadata().num_row_groups(), 3);
+}
@@ -0,0 +1,69 @@
+use dm_database_parser_sqllog::LogParser;
+use std::io::Cursor;
+
+const LOG: &str = concat!(
+    "2025-11-17 16:09:41.123 (EP[0] sess:1 thrd:2 user:u trxid:3 stmt:4 appname:app) SELECT 1\n",
+    "2025-11-17 16:09:42.123 (EP[0] sess:2 thrd:3 user:u trxid:3 stmt:4 appname:app) SELECT\n",
+    "  2 FROM dual EXECTIME: 1(ms) ROWCOUNT: 1(rows) EXEC_ID: 5.\n",
+);
+
+#[test]
+fn from_bytes_owned_vec() {
+    let parser = LogParser::from_bytes(LOG.as_bytes().to_vec());
+    let records: Vec<_> = parser.iter().map(|r| r.unwrap()).collect();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].body(), "SELECT 1");
+    assert_eq!(records[1].ts, "2025-11-17 16:09:42.123");
+    assert_eq!(records[1].parse_indicators().unwrap().exec_id, 5);
+}
+
+#[test]
+fn from_bytes_static_slice() {
+    let parser = LogParser::from_bytes(LOG.as_bytes());
+    assert_eq!(parser.iter().filter(|r| r.is_ok()).count(), 2);
+}
+
+#[test]
+fn from_bytes_empty() {
+    let parser = LogParser::from_bytes(Vec::new());
+    assert_eq!(parser.iter().count(), 0);
+    assert!(parser.index().is_empty());
+}
+
+#[test]
+fn from_bytes_detects_gb18030() {
+    // "用户" 的 GB18030 编码
+    let mut data = b"2025-11-17 16:09:41.123 (EP[0] sess:1 thrd:2 user:".to_vec();
+    data.extend_from_slice(&[0xD3, 0xC3, 0xBB, 0xA7]);
+    data.extend_from_slice(b" trxid:3 stmt:4 appname:app) SELECT 1\n");
+
+    let parser = LogParser::from_bytes(data);
+    let rec = parser.iter().next().unwrap().unwrap();
+    assert_eq!(rec.parse_meta().username, "用户");
+}
+
+#[test]
+#[cfg(not(miri))]
+fn from_reader_matches_par_iter() {
+    use rayon::prelude::*;
+
+    let parser = LogParser::from_reader(Cursor::new(LOG.as_bytes())).unwrap();
+    let seq = parser.iter().filter(|r| r.is_ok()).count();
+    let par = parser.par_iter().filter(|r| r.is_ok()).count();
+    assert_eq!(seq, 2);
+    assert_eq!(seq, par);
+}
+
+#[test]
+fn from_bytes_shared_non_static_buffer() {
+    use std::sync::Arc;
+
+    // 运行时构造的缓冲区：调用方与解析器共享同一份数据
+    let buf: Arc<[u8]> = Arc::from(LOG.replace("SELECT 1", "SELECT 42").into_bytes());
+    let parser = LogParser::from_bytes(Arc::clone(&buf));
+    assert_eq!(parser.as_bytes().as_ptr(), buf.as_ptr());
+
+    let records: Vec<_> = parser.iter().map(|r| r.unwrap()).collect();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].body(), "SELECT 42");
+}
@@ -168,7 +168,7 @@ fn par_iter_yields_same_count_as_iter_large_multiline() {
     let mut written = 0usize;
     let mut idx = 0usize;
     while written < target {
-        let rec = if idx % 5 == 0 {
+        let rec = if idx.is_multiple_of(5) {
             multi.as_ref()
         } else {
             single.as_ref()
@@ -209,3 +209,41 @@ fn par_iter_small_file_single_partition() {
     assert_eq!(seq_count, par_count);
     assert_eq!(par_count, 10);
 }
+
+#[test]
+#[cfg(not(miri))]
+fn par_iter_chunk_tails_match_iter_on_large_input() {
+    // 超过 32 MiB 才会按线程数切块；块末记录的正文不能多带分隔换行符
+    let mut data = Vec::with_capacity(33 * 1024 * 1024);
+    let mut i = 0u32;
+    while data.len() < 33 * 1024 * 1024 {
+        data.extend_from_slice(
+            make_record(
+                &format!("5-01-01 10:{:02}:{:02}.000", (i / 60) % 60, i % 60),
+                &format!("SELECT {i}"),
+            )
+            .as_bytes(),
+        );
+        i += 1;
+    }
+    let parser = LogParser::from_bytes(data);
+
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(4)
+        .build()
+        .unwrap();
+    let mut parallel: Vec<String> = pool.install(|| {
+        parser
+            .par_iter()
+            .map(|r| r.unwrap().body().to_string())
+            .collect()
+    });
+    let mut sequential: Vec<String> = parser
+        .iter()
+        .map(|r| r.unwrap().body().to_string())
+        .collect();
+    assert_eq!(parallel.len(), i as usize);
+    parallel.sort();
+    sequential.sort();
+    assert_eq!(parallel, sequential);
+}
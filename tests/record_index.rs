@@ -0,0 +1,147 @@
+use dm_database_parser_sqllog::{LogParser, ParseError, RecordIndex};
+use std::fs;
+
+const LOG: &str = "2025-08-12 10:57:09.548 (EP[0] sess:1 thrd:2 user:alice trxid:3 stmt:4 appname:app) SELECT 1\n\
+                   2025-08-12 10:57:10.000 (EP[0] sess:1 thrd:2 user:bob trxid:3 stmt:4 appname:app) SELECT *\n\
+                   FROM T\n\
+                   WHERE X = 1\r\n\
+                   2025-08-12 10:57:11.250 (EP[0] sess:1 thrd:2 user:carol trxid:3 stmt:4 appname:app) [SEL] SELECT 3. EXECTIME: 1(ms) ROWCOUNT: 1(rows) EXEC_ID: 7.\n\
+                   2025-08-12 10:57:12.000 (EP[0] sess:1 thrd:2 user:dave trxid:3 stmt:4 appname:app) SELECT 4";
+
+#[test]
+fn get_matches_sequential_iteration() {
+    let parser = LogParser::from_bytes(LOG.as_bytes());
+    let index = parser.index();
+    assert_eq!(index.len(), 4);
+
+    for (n, expected) in parser.iter().enumerate() {
+        let expected = expected.unwrap();
+        let got = parser.get(&index, n).unwrap().unwrap();
+        assert_eq!(got, expected, "record {n}");
+        assert_eq!(index.timestamp_millis(n), expected.timestamp_millis());
+    }
+    assert!(parser.get(&index, 4).is_none());
+    assert!(index.timestamp_millis(4).is_none());
+}
+
+#[test]
+fn iter_index_pages_through_records() {
+    let parser = LogParser::from_bytes(LOG.as_bytes());
+    let index = parser.index();
+    let users = |it: dm_database_parser_sqllog::LogIterator<'_>| -> Vec<String> {
+        it.map(|r| r.unwrap().parse_meta().username.into_owned())
+            .collect()
+    };
+
+    assert_eq!(
+        users(parser.iter_index(&index, 1..3).unwrap()),
+        ["bob", "carol"]
+    );
+    assert_eq!(
+        users(parser.iter_index(&index, ..=1).unwrap()),
+        ["alice", "bob"]
+    );
+    assert_eq!(users(parser.iter_index(&index, 3..).unwrap()), ["dave"]);
+    assert!(users(parser.iter_index(&index, 10..20).unwrap()).is_empty());
+
+    let range = index.byte_range(1).unwrap();
+    assert_eq!(range.start, index.offsets()[1]);
+    assert_eq!(range.end, index.offsets()[2]);
+}
+
+#[test]
+fn index_from_other_data_is_rejected() {
+    let long = LogParser::from_bytes(LOG.as_bytes());
+    let short = LogParser::from_bytes(&LOG.as_bytes()[..200]);
+    let index = long.index();
+    assert!(matches!(
+        short.get(&index, 3),
+        Some(Err(ParseError::InvalidOffset { .. }))
+    ));
+}
+
+#[test]
+fn sidecar_roundtrip_and_staleness() {
+    let dir = tempfile::tempdir().unwrap();
+    let log_path = dir.path().join("dmsql_DMSERVER_20250812105709.log");
+    fs::write(&log_path, LOG).unwrap();
+    let idx_path = RecordIndex::sidecar_path(&log_path);
+    assert_eq!(
+        idx_path.file_name().unwrap(),
+        "dmsql_DMSERVER_20250812105709.log.idx"
+    );
+
+    let parser = LogParser::builder()
+        .utc_offset(8 * 3600)
+        .open(&log_path)
+        .unwrap();
+    let index = parser.index();
+    index.save(&idx_path, &log_path).unwrap();
+
+    let loaded = RecordIndex::load(&idx_path, &log_path).unwrap();
+    assert_eq!(loaded, index);
+    assert_eq!(
+        loaded.timestamp_millis(0),
+        Some(1_754_996_229_548 - 8 * 3_600_000)
+    );
+    let rec = parser.get(&loaded, 2).unwrap().unwrap();
+    assert_eq!(rec.parse_meta().username, "carol");
+    assert_eq!(rec.parse_performance_metrics().exec_id, 7);
+
+    // 追加写入后索引失效
+    let mut grown = LOG.to_string();
+    grown.push_str("\n2025-08-12 10:57:13.000 (EP[0] sess:1 thrd:2 user:erin trxid:3 stmt:4 appname:app) SELECT 5");
+    fs::write(&log_path, grown).unwrap();
+    assert!(matches!(
+        RecordIndex::load(&idx_path, &log_path),
+        Err(ParseError::IndexMismatch { .. })
+    ));
+
+    fs::write(&idx_path, b"DMSQLIDX\x01\x00\x00\x00").unwrap();
+    assert!(matches!(
+        RecordIndex::load(&idx_path, &log_path),
+        Err(ParseError::InvalidFormat { .. })
+    ));
+}
+
+#[test]
+fn sidecar_uses_stamp_from_index_time() {
+    let dir = tempfile::tempdir().unwrap();
+    let log_path = dir.path().join("dmsql_DMSERVER_20250812105709.log");
+    fs::write(&log_path, LOG).unwrap();
+    let idx_path = RecordIndex::sidecar_path(&log_path);
+
+    let parser = LogParser::from_path(&log_path).unwrap();
+    let index = parser.index();
+
+    // 在 index() 与 save() 之间文件被追加写入：保存的索引不应通过校验
+    let mut grown = LOG.to_string();
+    grown.push_str("\n2025-08-12 10:57:13.000 (EP[0] sess:1 thrd:2 user:erin trxid:3 stmt:4 appname:app) SELECT 5");
+    fs::write(&log_path, grown).unwrap();
+    index.save(&idx_path, &log_path).unwrap();
+    assert!(matches!(
+        RecordIndex::load(&idx_path, &log_path),
+        Err(ParseError::IndexMismatch { .. })
+    ));
+}
+
+#[test]
+fn sidecar_rejects_length_mismatch_for_mapped_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let log_path = dir.path().join("dmsql_DMSERVER_20250812105709.log");
+    fs::write(&log_path, LOG).unwrap();
+    let idx_path = RecordIndex::sidecar_path(&log_path);
+
+    let parser = LogParser::from_path(&log_path).unwrap();
+    parser.index().save(&idx_path, &log_path).unwrap();
+    assert!(RecordIndex::load(&idx_path, &log_path).is_ok());
+
+    // 文件状态不变，但索引记录的数据长度（头部偏移 36）与文件长度不符
+    let mut bytes = fs::read(&idx_path).unwrap();
+    bytes[36..44].copy_from_slice(&(LOG.len() as u64 + 1).to_le_bytes());
+    fs::write(&idx_path, &bytes).unwrap();
+    assert!(matches!(
+        RecordIndex::load(&idx_path, &log_path),
+        Err(ParseError::IndexMismatch { .. })
+    ));
+}
@@ -0,0 +1,81 @@
+use dm_database_parser_sqllog::{LogParser, Sqllog};
+
+const LOG: &str = "garbage before first record\n\
+                   2025-08-12 10:57:09.548 (EP[0] sess:1 thrd:2 user:alice trxid:3 stmt:4 appname:app) SELECT 1\n\
+                   \n\
+                   2025-08-12 10:57:10.000 (EP[0] sess:1 thrd:2 user:bob trxid:3 stmt:4 appname:app) INSERT INTO T VALUES\r\n\
+                   2025-08-12 is not a timestamp line\r\n\
+                   (2)\r\n\
+                   2025-08-12 10:57:11.250 (EP[0] sess:1 thrd:2 user:carol trxid:3 stmt:4 appname:app) [SEL] SELECT 3. EXECTIME: 1(ms) ROWCOUNT: 1(rows) EXEC_ID: 7.\n\
+                   2025-08-12 10:57:12.000 (EP[0] sess:1 thrd:2 user:dave trxid:3 stmt:4 appname:app) SELECT *\n\
+                   FROM T\n";
+
+fn collect<'a>(
+    it: impl Iterator<Item = Result<Sqllog<'a>, dm_database_parser_sqllog::ParseError>>,
+) -> Vec<Result<Sqllog<'a>, String>> {
+    it.map(|r| r.map_err(|e| e.to_string())).collect()
+}
+
+#[test]
+fn reverse_matches_forward_reversed() {
+    for log in [LOG, LOG.trim_end(), &LOG[28..]] {
+        let parser = LogParser::from_bytes(log.as_bytes().to_vec());
+        let mut forward = collect(parser.iter());
+        forward.reverse();
+        assert_eq!(collect(parser.iter_rev()), forward);
+    }
+}
+
+#[test]
+fn reverse_handles_multiline_bodies() {
+    let parser = LogParser::from_bytes(LOG.as_bytes());
+    let users: Vec<String> = parser
+        .iter_rev()
+        .filter_map(Result::ok)
+        .map(|r| r.parse_meta().username.into_owned())
+        .collect();
+    assert_eq!(users, ["dave", "carol", "bob", "alice"]);
+
+    let bob = parser.iter_rev().nth(2).unwrap().unwrap();
+    assert_eq!(
+        bob.body(),
+        "INSERT INTO T VALUES\r\n2025-08-12 is not a timestamp line\r\n(2)"
+    );
+}
+
+#[test]
+fn front_and_back_meet_in_the_middle() {
+    let parser = LogParser::from_bytes(LOG.as_bytes());
+    let expected = collect(parser.iter());
+
+    let mut it = parser.iter();
+    let mut front = Vec::new();
+    let mut back = Vec::new();
+    while let Some(r) = it.next() {
+        front.push(r.map_err(|e| e.to_string()));
+        match it.next_back() {
+            Some(r) => back.push(r.map_err(|e| e.to_string())),
+            None => break,
+        }
+    }
+    back.reverse();
+    front.extend(back);
+    assert_eq!(front, expected);
+}
+
+#[test]
+fn last_n_reads_only_the_tail() {
+    let mut log = String::new();
+    for i in 0..10_000 {
+        log.push_str(&format!(
+            "2025-08-12 10:57:09.548 (EP[0] sess:1 thrd:2 user:u trxid:3 stmt:4 appname:app) SELECT {i}\n"
+        ));
+    }
+    let parser = LogParser::from_bytes(log.into_bytes());
+    let last: Vec<String> = parser
+        .iter_rev()
+        .take(3)
+        .map(|r| r.unwrap().body().trim_end().to_string())
+        .collect();
+    assert_eq!(last, ["SELECT 9999", "SELECT 9998", "SELECT 9997"]);
+}
@@ -0,0 +1,105 @@
+#![cfg(feature = "serde")]
+
+use std::borrow::Cow;
+
+use dm_database_parser_sqllog::{
+    Encoding, LogParser, MetaParts, PerformanceMetrics, Sqllog, SqllogView,
+};
+use encoding::all::GB18030;
+use encoding::{EncoderTrap, Encoding as _};
+
+const LOG: &str = "2025-08-12 10:57:09.548 (EP[1] sess:0x1 thrd:2 user:alice trxid:3 stmt:0x4 appname:disql ip:::ffff:10.0.0.1) [SEL] SELECT 1. EXECTIME: 1.5(ms) ROWCOUNT: 1(rows) EXEC_ID: 100.\n\
+                   2025-08-12 10:57:10.000 (EP[0] sess:0x1 thrd:2 user:alice trxid:3 stmt:0x5 appname:disql) [ORA]: SELECT *\n\
+                   FROM DUAL\n";
+
+#[test]
+fn view_serializes_parsed_fields() {
+    let parser = LogParser::from_bytes(LOG.as_bytes());
+    let records: Vec<_> = parser.iter().map(Result::unwrap).collect();
+
+    let json = serde_json::to_value(records[0].view()).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "ts": "2025-08-12 10:57:09.548",
+            "ep": 1,
+            "sess_id": "0x1",
+            "thrd_id": "2",
+            "username": "alice",
+            "trxid": "3",
+            "statement": "0x4",
+            "appname": "disql",
+            "client_ip": "::ffff:10.0.0.1",
+            "tag": "SEL",
+            "sql": "SELECT 1. ",
+            "exectime": 1.5,
+            "rowcount": 1,
+            "exec_id": 100
+        })
+    );
+
+    let json = serde_json::to_value(records[1].view()).unwrap();
+    assert_eq!(json["sql"], "SELECT *\nFROM DUAL\n");
+    assert!(json["client_ip"].is_null());
+    assert!(json["exec_id"].is_null());
+}
+
+#[test]
+fn view_round_trip_borrows_strings() {
+    let parser = LogParser::from_bytes(LOG.as_bytes());
+    let view = parser.iter().next().unwrap().unwrap().view();
+    let text = serde_json::to_string(&view).unwrap();
+
+    let back: SqllogView<'_> = serde_json::from_str(&text).unwrap();
+    assert_eq!(back, view);
+    assert!(matches!(back.username, Cow::Borrowed(_)));
+}
+
+#[test]
+fn sqllog_round_trip_keeps_encoding() {
+    let text = "2025-08-12 10:57:09.548 (EP[0] sess:1 thrd:2 user:用户 trxid:3 stmt:4 appname:app) SELECT '数据'\n";
+    let parser = LogParser::builder()
+        .encoding(Encoding::Gb18030)
+        .utc_offset(8 * 3600)
+        .open_bytes(GB18030.encode(text, EncoderTrap::Strict).unwrap());
+    let record = parser.iter().next().unwrap().unwrap();
+
+    let json = serde_json::to_string(&record).unwrap();
+    let back: Sqllog<'_> = serde_json::from_str(&json).unwrap();
+    assert_eq!(back, record);
+    assert_eq!(back.body(), "SELECT '数据'\n");
+    assert_eq!(back.utc_offset(), 8 * 3600);
+    assert_eq!(back.record_encoding(), Some(Encoding::Gb18030));
+}
+
+#[test]
+fn meta_and_metrics_derive_serde() {
+    let parser = LogParser::from_bytes(LOG.as_bytes());
+    let record = parser.iter().next().unwrap().unwrap();
+
+    let meta = record.parse_meta();
+    let json = serde_json::to_string(&meta).unwrap();
+    assert_eq!(serde_json::from_str::<MetaParts<'_>>(&json).unwrap(), meta);
+
+    let pm = record.parse_performance_metrics();
+    let json = serde_json::to_string(&pm).unwrap();
+    let back: PerformanceMetrics<'_> = serde_json::from_str(&json).unwrap();
+    assert_eq!(back, pm);
+    assert!(matches!(back.sql, Cow::Borrowed(_)));
+}
+
+#[test]
+fn sqllog_deserialize_validates_decoding_state() {
+    let json = r#"{"ts":"2025-08-12 10:57:09.548","meta_raw":"EP[0] sess:1 thrd:2 user:u trxid:3 stmt:4 appname:app","content_raw":[255,254,65],"tag":null,"encoding":"Utf8","lossy":false,"utc_offset":0}"#;
+    // 声明为 UTF-8 的非法字节改为有损解码，而不是按 UTF-8 直接借用
+    let record: Sqllog<'_> = serde_json::from_str(json).unwrap();
+    assert_eq!(record.body(), "\u{FFFD}\u{FFFD}A");
+    assert!(record.is_lossy());
+    assert_eq!(record.record_encoding(), Some(Encoding::Utf8));
+
+    let bad_offset = json.replace(r#""utc_offset":0"#, r#""utc_offset":99999999"#);
+    assert!(serde_json::from_str::<Sqllog<'_>>(&bad_offset).is_err());
+
+    let bad_encoding = json.replace(r#""Utf8""#, r#""Latin1""#);
+    assert!(serde_json::from_str::<Sqllog<'_>>(&bad_encoding).is_err());
+}
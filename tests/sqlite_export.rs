@@ -0,0 +1,155 @@
+#![cfg(feature = "sqlite")]
+
+use dm_database_parser_sqllog::{LogParser, SqliteSink};
+use rusqlite::Connection;
+
+const LOG: &str = "2025-08-12 10:57:09.548 (EP[1] sess:0x1 thrd:2 user:alice trxid:3 stmt:0x4 appname:disql ip:::ffff:10.0.0.1) [SEL] SELECT 1. EXECTIME: 1.5(ms) ROWCOUNT: 1(rows) EXEC_ID: 100.\n\
+                   2025-08-12 10:57:10.000 (EP[0] sess:0x1 thrd:2 user:alice trxid:3 stmt:0x5 appname:disql) [ORA]: SELECT *\n\
+                   FROM DUAL\n\
+                   2025-08-12 10:57:11.000 (EP[0] sess:0x2 thrd:3 user:bob trxid:4 stmt:0x6 appname:jdbc) COMMIT\n";
+
+#[test]
+fn insert_all_creates_table() {
+    let parser = LogParser::from_bytes(LOG.as_bytes());
+    let mut sink = SqliteSink::from_connection(Connection::open_in_memory().unwrap()).batch_size(2);
+    let rows = sink
+        .insert_all(parser.iter().filter_map(Result::ok))
+        .unwrap();
+    assert_eq!(rows, 3);
+    let conn = sink.finish().unwrap();
+
+    let (user, ip, exectime, exec_id): (String, Option<String>, Option<f64>, Option<i64>) = conn
+        .query_row(
+            "SELECT username, client_ip, exectime, exec_id FROM sqllog WHERE ts = '2025-08-12 10:57:09.548'",
+            [],
+            |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?)),
+        )
+        .unwrap();
+    assert_eq!(user, "alice");
+    assert_eq!(ip.as_deref(), Some("::ffff:10.0.0.1"));
+    assert_eq!(exectime, Some(1.5));
+    assert_eq!(exec_id, Some(100));
+
+    let (sql, tag, rowcount): (String, Option<String>, Option<i64>) = conn
+        .query_row(
+            "SELECT sql, tag, rowcount FROM sqllog WHERE statement = '0x5'",
+            [],
+            |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
+        )
+        .unwrap();
+    assert_eq!(sql, "SELECT *\nFROM DUAL");
+    assert_eq!(tag.as_deref(), Some("ORA"));
+    assert_eq!(rowcount, None);
+}
+
+#[test]
+fn par_insert_with_indexes() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("sqllog.db");
+
+    let mut log = String::from("garbage line\n");
+    for i in 0..10_000 {
+        log.push_str(&format!(
+            "2025-08-12 10:57:09.548 (EP[0] sess:{i} thrd:2 user:u{} trxid:{i} stmt:4 appname:app) SELECT {i}. EXECTIME: 1(ms) ROWCOUNT: 1(rows) EXEC_ID: {i}.\n",
+            i % 5
+        ));
+    }
+    let parser = LogParser::from_bytes(log.into_bytes());
+
+    let mut sink = SqliteSink::open(&path)
+        .unwrap()
+        .table("day_log")
+        .batch_size(1_000)
+        .with_indexes(true);
+    let stats = sink.par_insert(&parser).unwrap();
+    assert_eq!(stats.rows, 10_000);
+    assert_eq!(stats.errors, 1);
+    drop(sink.finish().unwrap());
+
+    let conn = Connection::open(&path).unwrap();
+    let (count, sum): (i64, i64) = conn
+        .query_row("SELECT COUNT(*), SUM(exec_id) FROM day_log", [], |r| {
+            Ok((r.get(0)?, r.get(1)?))
+        })
+        .unwrap();
+    assert_eq!(count, 10_000);
+    assert_eq!(sum, (0..10_000).sum::<i64>());
+
+    let mut stmt = conn
+        .prepare("SELECT name FROM sqlite_master WHERE type = 'index' ORDER BY name")
+        .unwrap();
+    let indexes: Vec<String> = stmt
+        .query_map([], |r| r.get(0))
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(
+        indexes,
+        [
+            "day_log_exectime",
+            "day_log_trxid",
+            "day_log_ts",
+            "day_log_username"
+        ]
+    );
+}
+
+#[test]
+fn appends_to_existing_table() {
+    let parser = LogParser::from_bytes(LOG.as_bytes());
+    let conn = Connection::open_in_memory().unwrap();
+    let mut sink = SqliteSink::from_connection(conn);
+    sink.insert_all(parser.iter().filter_map(Result::ok))
+        .unwrap();
+    sink.insert_all(parser.iter().filter_map(Result::ok))
+        .unwrap();
+    let count: i64 = sink
+        .connection()
+        .query_row("SELECT COUNT(*) FROM sqllog", [], |r| r.get(0))
+        .unwrap();
+    assert_eq!(count, 6);
+}
+
+#[test]
+fn appends_to_table_with_different_column_order() {
+    let parser = LogParser::from_bytes(LOG.as_bytes());
+    let conn = Connection::open_in_memory().unwrap();
+    conn.execute_batch(
+        "CREATE TABLE sqllog (
+            id INTEGER PRIMARY KEY,
+            sql TEXT NOT NULL,
+            ts TEXT NOT NULL,
+            exec_id INTEGER,
+            exectime REAL,
+            rowcount INTEGER,
+            tag TEXT,
+            client_ip TEXT,
+            appname TEXT NOT NULL,
+            statement TEXT NOT NULL,
+            trxid TEXT NOT NULL,
+            username TEXT NOT NULL,
+            thrd_id TEXT NOT NULL,
+            sess_id TEXT NOT NULL,
+            ep INTEGER NOT NULL
+        )",
+    )
+    .unwrap();
+    let mut sink = SqliteSink::from_connection(conn);
+    let rows = sink
+        .insert_all(parser.iter().filter_map(Result::ok))
+        .unwrap();
+    assert_eq!(rows, 3);
+
+    let (id, ts, username, exec_id): (i64, String, String, i64) = sink
+        .connection()
+        .query_row(
+            "SELECT id, ts, username, exec_id FROM sqllog ORDER BY id LIMIT 1",
+            [],
+            |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?)),
+        )
+        .unwrap();
+    assert_eq!(id, 1);
+    assert_eq!(ts, "2025-08-12 10:57:09.548");
+    assert_eq!(username, "alice");
+    assert_eq!(exec_id, 100);
+}
@@ -248,7 +248,7 @@ fn file_encoding_detection_utf8() {
     let user_bytes = username.as_bytes();
 
     let mut line: Vec<u8> = b"2025-11-17 16:09:41.123 (EP[2] sess:0xABC thrd:777 user:".to_vec();
-    line.extend_from_slice(&user_bytes);
+    line.extend_from_slice(user_bytes);
     line.extend_from_slice(b" trxid:0 stmt:0x2 appname:cli) SELECT\n");
 
     let mut tmp = NamedTempFile::new().expect("tmp");
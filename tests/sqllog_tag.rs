@@ -0,0 +1,100 @@
+use dm_database_parser_sqllog::{LogParser, SqllogTag, StatementKind};
+
+const LOG: &str = "2025-08-12 10:57:09.548 (EP[0] sess:1 thrd:2 user:a trxid:3 stmt:4 appname:app) [SEL] SELECT 1\n\
+                   2025-08-12 10:57:09.549 (EP[0] sess:1 thrd:2 user:a trxid:3 stmt:4 appname:app) [ORA]: SELECT 2\n\
+                   2025-08-12 10:57:09.550 (EP[0] sess:1 thrd:2 user:a trxid:3 stmt:4 appname:app) [T1] SELECT 3\n\
+                   2025-08-12 10:57:09.551 (EP[0] sess:1 thrd:2 user:a trxid:3 stmt:4 appname:app) COMMIT\n";
+
+#[test]
+fn parses_known_and_other_tags() {
+    assert_eq!(SqllogTag::parse("SEL"), SqllogTag::Sel);
+    assert_eq!(SqllogTag::parse("ddl"), SqllogTag::Ddl);
+    assert_eq!(SqllogTag::parse("ORA"), SqllogTag::Ora);
+    assert_eq!(SqllogTag::parse("T1"), SqllogTag::Other("T1".to_string()));
+    assert_eq!(SqllogTag::parse("upd").to_string(), "UPD");
+    assert_eq!(SqllogTag::Other("x".to_string()).as_str(), "x");
+    assert_eq!(SqllogTag::Del.statement_kind(), Some(StatementKind::Delete));
+    assert_eq!(SqllogTag::Ora.statement_kind(), None);
+}
+
+#[test]
+fn accepts_any_tag_by_default() {
+    let parser = LogParser::from_bytes(LOG.as_bytes());
+    let tags: Vec<_> = parser.iter().map(|r| r.unwrap().tag_kind()).collect();
+    assert_eq!(
+        tags,
+        [
+            Some(SqllogTag::Sel),
+            Some(SqllogTag::Ora),
+            Some(SqllogTag::Other("T1".to_string())),
+            None
+        ]
+    );
+}
+
+#[test]
+fn unrecognized_tags_stay_in_body() {
+    let parser = LogParser::builder()
+        .recognized_tags([SqllogTag::Sel, SqllogTag::Ora])
+        .open_bytes(LOG.as_bytes());
+    let records: Vec<_> = parser.iter().map(|r| r.unwrap()).collect();
+    assert_eq!(records[0].tag_kind(), Some(SqllogTag::Sel));
+    assert_eq!(records[1].tag_kind(), Some(SqllogTag::Ora));
+    assert_eq!(records[2].tag, None);
+    assert!(records[2].body().starts_with("[T1] SELECT 3"));
+}
+
+#[test]
+fn empty_list_disables_tags() {
+    let parser = LogParser::builder()
+        .recognized_tags([])
+        .open_bytes(LOG.as_bytes());
+    let first = parser.iter().next().unwrap().unwrap();
+    assert_eq!(first.tag, None);
+    assert!(first.body().starts_with("[SEL] SELECT 1"));
+}
+
+#[test]
+fn other_accepts_only_listed_names() {
+    let log = format!(
+        "{LOG}2025-08-12 10:57:09.552 (EP[0] sess:1 thrd:2 user:a trxid:3 stmt:4 appname:app) [XYZ] SELECT 4\n"
+    );
+    let parser = LogParser::builder()
+        .recognized_tags([SqllogTag::Other("t1".to_string())])
+        .open_bytes(log.into_bytes());
+    let records: Vec<_> = parser.iter().map(|r| r.unwrap()).collect();
+    assert_eq!(records[0].tag, None);
+    assert_eq!(records[2].tag.as_deref(), Some("T1"));
+    assert_eq!(records[4].tag, None);
+    assert!(records[4].body().starts_with("[XYZ] SELECT 4"));
+}
+
+#[test]
+fn listed_other_does_not_accept_other_unknown_tags() {
+    let parser = LogParser::builder()
+        .recognized_tags([
+            SqllogTag::Other("CALL".to_string()),
+            SqllogTag::parse("sel"),
+        ])
+        .open_bytes(LOG.as_bytes());
+    let records: Vec<_> = parser.iter().map(|r| r.unwrap()).collect();
+    assert_eq!(records[0].tag_kind(), Some(SqllogTag::Sel));
+    assert_eq!(records[2].tag, None);
+    assert!(records[2].body().starts_with("[T1] SELECT 3"));
+}
+
+#[test]
+fn lowercase_ora_tag_strips_prefix() {
+    let log = "2025-08-12 10:57:09.549 (EP[0] sess:1 thrd:2 user:a trxid:3 stmt:4 appname:app) [ora]: SELECT 2 EXECTIME: 1(ms) ROWCOUNT: 1(rows) EXEC_ID: 7.\n";
+    let parser = LogParser::from_bytes(log.as_bytes());
+    let record = parser.iter().next().unwrap().unwrap();
+    assert_eq!(record.tag_kind(), Some(SqllogTag::Ora));
+    let pm = record.parse_performance_metrics();
+    assert_eq!(pm.sql.trim_end(), "SELECT 2");
+    assert_eq!(pm.exec_id, 7);
+
+    let mut batches = parser.batches(1);
+    let batch = batches.next_batch().unwrap();
+    let body = &parser.as_bytes()[batch.body_ranges()[0].clone()];
+    assert_eq!(body.trim_ascii_end(), b"SELECT 2");
+}
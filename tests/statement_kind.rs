@@ -0,0 +1,51 @@
+use dm_database_parser_sqllog::{LogParser, StatementKind};
+
+#[test]
+fn classifies_by_first_keyword() {
+    let cases = [
+        ("SELECT 1", StatementKind::Select),
+        (
+            "with x as (select 1) select * from x",
+            StatementKind::Select,
+        ),
+        (
+            "  -- comment\n  INSERT INTO t VALUES (1)",
+            StatementKind::Insert,
+        ),
+        ("/* a */ /* b */ update t set a = 1", StatementKind::Update),
+        ("DELETE FROM t", StatementKind::Delete),
+        ("MERGE INTO t USING s ON (1 = 1)", StatementKind::Merge),
+        ("create table t (a int)", StatementKind::Ddl),
+        ("GRANT SELECT ON t TO u", StatementKind::Ddl),
+        ("commit", StatementKind::Commit),
+        ("ROLLBACK TO SAVEPOINT sp", StatementKind::Rollback),
+        ("CALL p(1)", StatementKind::Call),
+        ("begin p(1); end;", StatementKind::Call),
+        ("SET SCHEMA s", StatementKind::Set),
+        ("SELECTX", StatementKind::Other),
+        ("", StatementKind::Other),
+        ("/* unterminated", StatementKind::Other),
+    ];
+    for (sql, kind) in cases {
+        assert_eq!(StatementKind::from_sql(sql), kind, "{sql}");
+    }
+}
+
+#[test]
+fn record_kind_prefers_tag() {
+    let log = "2025-08-12 10:57:09.548 (EP[0] sess:1 thrd:2 user:a trxid:3 stmt:4 appname:app) [SEL] call p(1)\n\
+               2025-08-12 10:57:09.549 (EP[0] sess:1 thrd:2 user:a trxid:3 stmt:4 appname:app) [ORA]: /* x */ UPDATE t SET a = 1\n\
+               2025-08-12 10:57:09.550 (EP[0] sess:1 thrd:2 user:a trxid:3 stmt:4 appname:app) COMMIT\n\
+               2025-08-12 10:57:09.551 (EP[0] sess:1 thrd:2 user:a trxid:3 stmt:4 appname:app) [ins] insert into t values (1)\n";
+    let parser = LogParser::from_bytes(log.as_bytes());
+    let kinds: Vec<_> = parser.iter().map(|r| r.unwrap().statement_kind()).collect();
+    assert_eq!(
+        kinds,
+        [
+            StatementKind::Select,
+            StatementKind::Update,
+            StatementKind::Commit,
+            StatementKind::Insert
+        ]
+    );
+}
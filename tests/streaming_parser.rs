@@ -0,0 +1,122 @@
+use dm_database_parser_sqllog::{
+    DecodePolicy, Encoding, LogParser, LogParserBuilder, ParseError, SqllogTag, StreamingLogParser,
+};
+use std::io::{BufReader, Cursor};
+
+const LOG: &str = concat!(
+    "\n",
+    "2025-11-17 16:09:41.123 (EP[0] sess:1 thrd:2 user:u trxid:3 stmt:4 appname:app) SELECT 1\r\n",
+    "2025-11-17 16:09:42.123 (EP[0] sess:2 thrd:3 user:u trxid:3 stmt:4 appname:app) SELECT\n",
+    "  *\n",
+    "  FROM dual\n",
+    "EXECTIME: 2(ms) ROWCOUNT: 1(rows) EXEC_ID: 7.\n",
+    "2025-11-17 16:09:43.123 (EP[0] sess:3 thrd:4 user:u trxid:3 stmt:4 appname:app) [SEL] SELECT 3",
+);
+
+fn collect_stream(capacity: usize) -> Vec<(String, String)> {
+    let reader = BufReader::with_capacity(capacity, Cursor::new(LOG.as_bytes()));
+    StreamingLogParser::new(reader)
+        .map(|r| {
+            let r = r.unwrap();
+            (r.ts.to_string(), r.body().into_owned())
+        })
+        .collect()
+}
+
+#[test]
+fn streaming_matches_mmap_iter_for_every_buffer_size() {
+    let parser = LogParser::from_bytes(LOG.as_bytes());
+    let expected: Vec<(String, String)> = parser
+        .iter()
+        .map(|r| {
+            let r = r.unwrap();
+            (r.ts.to_string(), r.body().into_owned())
+        })
+        .collect();
+    assert_eq!(expected.len(), 3);
+
+    // 缓冲区从 1 字节到大于整个输入，覆盖边界跨越 refill 的所有位置
+    for capacity in 1..=LOG.len() + 1 {
+        assert_eq!(collect_stream(capacity), expected, "capacity = {capacity}");
+    }
+}
+
+#[test]
+fn streaming_multiline_record_keeps_indicators() {
+    let reader = BufReader::with_capacity(5, Cursor::new(LOG.as_bytes()));
+    let records: Vec<_> = StreamingLogParser::new(reader)
+        .map(|r| r.unwrap())
+        .collect();
+    let ind = records[1].parse_indicators().unwrap();
+    assert_eq!(ind.exec_id, 7);
+    assert_eq!(records[2].tag.as_deref(), Some("SEL"));
+}
+
+#[test]
+fn streaming_empty_input() {
+    let mut it = StreamingLogParser::new(Cursor::new(Vec::new()));
+    assert!(it.next().is_none());
+}
+
+#[test]
+fn streaming_gb18030_record() {
+    let mut data = b"2025-11-17 16:09:41.123 (EP[0] sess:1 thrd:2 user:".to_vec();
+    data.extend_from_slice(&[0xD3, 0xC3, 0xBB, 0xA7]);
+    data.extend_from_slice(b" trxid:3 stmt:4 appname:app) SELECT 1\n");
+
+    let rec = StreamingLogParser::new(Cursor::new(data))
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(rec.parse_meta().username, "用户");
+    assert_eq!(rec.body(), "SELECT 1\n");
+}
+
+/// 用户名为 Big5 编码的"中文"，正文以非标准标记 `[T1]` 开头
+fn big5_record() -> Vec<u8> {
+    let mut data = b"2025-08-12 10:00:01.000 (EP[0] sess:1 thrd:2 user:".to_vec();
+    data.extend_from_slice(&[0xA4, 0xA4, 0xA4, 0xE5]);
+    data.extend_from_slice(b" trxid:3 stmt:4 appname:app) [T1] SELECT 1\n");
+    data
+}
+
+fn big5_builder() -> LogParserBuilder {
+    LogParser::builder()
+        .encoding(Encoding::Big5)
+        .utc_offset(8 * 3600)
+        .recognized_tags([SqllogTag::Sel])
+}
+
+#[test]
+fn streaming_applies_builder_options() {
+    let data = big5_record();
+    let rec = StreamingLogParser::new(data.as_slice())
+        .options(big5_builder())
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(rec.parse_meta().username, "中文");
+    assert_eq!(rec.record_encoding(), Some(Encoding::Big5));
+    assert_eq!(rec.utc_offset(), 8 * 3600);
+    assert_eq!(rec.tag, None);
+    assert_eq!(rec.body(), "[T1] SELECT 1\n");
+
+    // 默认选项：逐记录判定，Big5 字节按 GB18030 解码
+    let rec = StreamingLogParser::new(data.as_slice())
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_ne!(rec.parse_meta().username, "中文");
+    assert_eq!(rec.utc_offset(), 0);
+    assert_eq!(rec.tag.as_deref(), Some("T1"));
+
+    // 强制 UTF-8 + Strict：非法字节返回错误
+    let strict = LogParser::builder()
+        .encoding(Encoding::Utf8)
+        .decode_policy(DecodePolicy::Strict);
+    let result = StreamingLogParser::new(data.as_slice())
+        .options(strict)
+        .next()
+        .unwrap();
+    assert!(matches!(result, Err(ParseError::Encoding { .. })));
+}
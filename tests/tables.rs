@@ -0,0 +1,138 @@
+use dm_database_parser_sqllog::{LogParser, TableAccess, referenced_tables};
+
+fn tables(sql: &str) -> Vec<(String, TableAccess)> {
+    referenced_tables(sql)
+        .iter()
+        .map(|t| (t.to_string(), t.access))
+        .collect()
+}
+
+fn read(name: &str) -> (String, TableAccess) {
+    (name.to_string(), TableAccess::Read)
+}
+
+fn write(name: &str) -> (String, TableAccess) {
+    (name.to_string(), TableAccess::Write)
+}
+
+#[test]
+fn select_from_and_joins() {
+    assert_eq!(
+        tables(
+            "SELECT a.x, EXTRACT(YEAR FROM b.d) FROM s1.t1 a, t2 AS b \
+             LEFT JOIN \"Sch\".\"My \"\"T\"\"\" c ON c.id = a.id \
+             WHERE a.id IN (SELECT id FROM s1.t3) ORDER BY 1"
+        ),
+        [
+            read("S1.T1"),
+            read("T2"),
+            read("Sch.My \"T\""),
+            read("S1.T3")
+        ]
+    );
+    assert_eq!(tables("select * from dual"), [read("DUAL")]);
+    assert_eq!(tables("select * from table(f(1))"), []);
+}
+
+#[test]
+fn dml_targets_are_written() {
+    assert_eq!(
+        tables("INSERT INTO app.orders (id) SELECT id FROM staging"),
+        [write("APP.ORDERS"), read("STAGING")]
+    );
+    assert_eq!(
+        tables("update app.t x set v = (select max(v) from u) where id = 1"),
+        [write("APP.T"), read("U")]
+    );
+    assert_eq!(tables("DELETE FROM t WHERE id = 1"), [write("T")]);
+    assert_eq!(tables("delete t"), [write("T")]);
+    assert_eq!(
+        tables("select * from t where id = 1 for update"),
+        [read("T")]
+    );
+    assert_eq!(
+        tables(
+            "MERGE INTO tgt t USING src s ON (t.id = s.id) \
+             WHEN MATCHED THEN UPDATE SET t.v = s.v \
+             WHEN NOT MATCHED THEN INSERT (id, v) VALUES (s.id, s.v)"
+        ),
+        [write("TGT"), read("SRC")]
+    );
+    assert_eq!(
+        tables("INSERT ALL INTO a VALUES (1) INTO b VALUES (2) SELECT * FROM dual"),
+        [write("A"), write("B"), read("DUAL")]
+    );
+}
+
+#[test]
+fn ddl_targets_are_written() {
+    assert_eq!(
+        tables("CREATE TABLE s.t2 AS SELECT * FROM s.t1"),
+        [write("S.T2"), read("S.T1")]
+    );
+    assert_eq!(tables("drop table if exists t"), [write("T")]);
+    assert_eq!(tables("TRUNCATE TABLE log_t"), [write("LOG_T")]);
+    assert_eq!(
+        tables("alter table t add constraint fk foreign key (a) references p (id)"),
+        [write("T")]
+    );
+    assert_eq!(
+        tables("create or replace view v as select * from t"),
+        [write("V"), read("T")]
+    );
+}
+
+#[test]
+fn objects_after_on_are_written() {
+    assert_eq!(
+        tables("CREATE UNIQUE INDEX idx_a ON app.orders (id, v)"),
+        [write("APP.ORDERS")]
+    );
+    assert_eq!(tables("create index i on t(a)"), [write("T")]);
+    assert_eq!(
+        tables("GRANT SELECT, UPDATE (v) ON app.\"Orders\" TO u1 WITH GRANT OPTION"),
+        [write("APP.Orders")]
+    );
+    assert_eq!(tables("GRANT CREATE TABLE TO u1"), []);
+    assert_eq!(tables("REVOKE INSERT ON TABLE s.t FROM u1"), [write("S.T")]);
+    assert_eq!(tables("COMMENT ON TABLE s.t IS 'orders'"), [write("S.T")]);
+    assert_eq!(
+        tables("COMMENT ON COLUMN s.t.c IS 'amount'"),
+        [write("S.T")]
+    );
+    assert_eq!(tables("comment on column t.c is 'x'"), [write("T")]);
+}
+
+#[test]
+fn ctes_and_duplicates_are_skipped() {
+    assert_eq!(
+        tables(
+            "WITH x AS (SELECT * FROM t), y AS (SELECT * FROM x) \
+             SELECT * FROM x JOIN y ON 1 = 1 JOIN t ON 1 = 1"
+        ),
+        [read("T")]
+    );
+    assert_eq!(
+        tables("insert into t select * from t"),
+        [write("T"), read("T")]
+    );
+}
+
+#[test]
+fn record_tables() {
+    let log = "2025-08-12 10:57:09.548 (EP[0] sess:1 thrd:2 user:a trxid:3 stmt:4 appname:app) [ORA]: UPDATE t SET a = 1. EXECTIME: 1(ms) ROWCOUNT: 1(rows) EXEC_ID: 1.\n\
+               2025-08-12 10:57:09.549 (EP[0] sess:1 thrd:2 user:a trxid:3 stmt:4 appname:app) [SEL] select * from sysdba.\"users\" -- from x\n";
+    let parser = LogParser::from_bytes(log.as_bytes());
+    let records: Vec<_> = parser.iter().map(|r| r.unwrap()).collect();
+    let first = records[0].tables();
+    assert_eq!(first.len(), 1);
+    assert_eq!(first[0].schema, None);
+    assert_eq!(first[0].name, "T");
+    assert_eq!(first[0].access, TableAccess::Write);
+
+    let second = records[1].tables();
+    assert_eq!(second.len(), 1);
+    assert_eq!(second[0].schema.as_deref(), Some("SYSDBA"));
+    assert_eq!(second[0].name, "users");
+    assert_eq!(second[0].access, TableAccess::Read);
+}
@@ -0,0 +1,137 @@
+use dm_database_parser_sqllog::{LogParser, ParseError};
+use std::time::Duration;
+
+fn ts(sec: u32) -> String {
+    format!(
+        "2025-08-12 {:02}:{:02}:{:02}.000",
+        10 + sec / 3600,
+        sec / 60 % 60,
+        sec % 60
+    )
+}
+
+fn line(sec: u32, sql: &str) -> String {
+    format!(
+        "{} (EP[0] sess:1 thrd:2 user:alice trxid:3 stmt:4 appname:app) {sql}\n",
+        ts(sec)
+    )
+}
+
+/// 每秒一条记录，共 `n` 条（远大于二分的顺序扫描窗口）
+fn ordered_log(n: u32) -> String {
+    (0..n)
+        .map(|i| line(i, &format!("SELECT {i} FROM T")))
+        .collect()
+}
+
+#[test]
+fn range_matches_linear_filter() {
+    let parser = LogParser::from_bytes(ordered_log(10_000).into_bytes());
+    for (start, end) in [(0, 3), (1234, 1300), (9990, 20_000), (5000, 5000)] {
+        let got: Vec<String> = parser
+            .range(&ts(start), &ts(end))
+            .unwrap()
+            .map(|r| r.unwrap().body().trim_end().to_string())
+            .collect();
+        let expected: Vec<String> = (start..end.min(10_000))
+            .map(|i| format!("SELECT {i} FROM T"))
+            .collect();
+        assert_eq!(got, expected, "[{start}, {end})");
+    }
+}
+
+#[test]
+fn range_accepts_truncated_bounds_and_multiline_records() {
+    let mut log = ordered_log(4000);
+    log.push_str(&line(4000, "SELECT *\nFROM T\n  WHERE X = 1"));
+    let parser = LogParser::from_bytes(log.into_bytes());
+
+    // 11:06 = 10:00 起第 3960 秒
+    let got: Vec<_> = parser
+        .range("2025-08-12 11:06", "2025-08-13")
+        .unwrap()
+        .map(|r| r.unwrap())
+        .collect();
+    assert_eq!(got.len(), 41);
+    assert_eq!(got[0].body().trim_end(), "SELECT 3960 FROM T");
+    assert_eq!(got[40].body().trim_end(), "SELECT *\nFROM T\n  WHERE X = 1");
+}
+
+#[test]
+fn tolerance_includes_out_of_order_records() {
+    let mut log = String::new();
+    for i in 0..3000 {
+        log.push_str(&line(i, &format!("SELECT {i} FROM T")));
+        if i == 1999 {
+            // 另一线程稍晚写入但时间戳更早的记录
+            log.push_str(&line(1500, "LATE START"));
+        }
+        if i == 2100 {
+            log.push_str(&line(2099, "LATE END"));
+        }
+    }
+    let parser = LogParser::from_bytes(log.into_bytes());
+
+    let strict: Vec<_> = parser
+        .range(&ts(1500), &ts(1501))
+        .unwrap()
+        .map(|r| r.unwrap().body().into_owned())
+        .collect();
+    assert_eq!(strict, ["SELECT 1500 FROM T"]);
+
+    let tolerant: Vec<_> = parser
+        .range_with_tolerance(&ts(1500), &ts(1501), Duration::from_secs(600))
+        .unwrap()
+        .map(|r| r.unwrap().body().trim_end().to_string())
+        .collect();
+    assert_eq!(tolerant, ["SELECT 1500 FROM T", "LATE START"]);
+
+    let tail: Vec<_> = parser
+        .range_with_tolerance(&ts(2050), &ts(2100), Duration::from_secs(5))
+        .unwrap()
+        .map(|r| r.unwrap().body().trim_end().to_string())
+        .collect();
+    assert_eq!(tail.len(), 51);
+    assert_eq!(tail.last().unwrap(), "LATE END");
+}
+
+#[test]
+fn range_rejects_malformed_bounds() {
+    let parser = LogParser::from_bytes(ordered_log(3).into_bytes());
+    for bad in [
+        "",
+        "yesterday",
+        "2025/08/12",
+        "2025-13-01",
+        "2025-08-12 10:00:00.0000",
+        // 截断在字段中间
+        "2025-08-1",
+        "2025-1",
+        "2025-08-12 1",
+        "2025-08-12 10:00:00.0",
+    ] {
+        assert!(
+            matches!(
+                parser.range(bad, "2026").err(),
+                Some(ParseError::InvalidFormat { .. })
+            ),
+            "{bad:?}"
+        );
+    }
+}
+
+#[test]
+fn range_accepts_every_field_boundary() {
+    let parser = LogParser::from_bytes(ordered_log(3).into_bytes());
+    for bound in [
+        "2025",
+        "2025-08",
+        "2025-08-12",
+        "2025-08-12 10",
+        "2025-08-12 10:00",
+        "2025-08-12 10:00:00",
+        "2025-08-12 10:00:00.000",
+    ] {
+        assert_eq!(parser.range(bound, "2026").unwrap().count(), 3, "{bound:?}");
+    }
+}
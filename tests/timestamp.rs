@@ -0,0 +1,90 @@
+use dm_database_parser_sqllog::{LogParser, parse_record};
+
+const LOG: &str = "2025-08-12 10:57:09.548 (EP[0] sess:1 thrd:2 user:alice trxid:3 stmt:4 appname:app) SELECT 1\n\
+                   2024-02-29 00:00:00.000 (EP[0] sess:1 thrd:2 user:alice trxid:3 stmt:4 appname:app) SELECT 2\n";
+
+#[test]
+fn timestamp_millis_defaults_to_utc() {
+    let parser = LogParser::from_bytes(LOG.as_bytes());
+    let millis: Vec<_> = parser
+        .iter()
+        .map(|r| r.unwrap().timestamp_millis())
+        .collect();
+    assert_eq!(millis, [Some(1_754_996_229_548), Some(1_709_164_800_000)]);
+}
+
+#[test]
+fn utc_offset_converts_local_time() {
+    let parser = LogParser::builder()
+        .utc_offset(8 * 3600)
+        .open_bytes(LOG.as_bytes());
+    let rec = parser.iter().next().unwrap().unwrap();
+    assert_eq!(rec.utc_offset(), 8 * 3600);
+    assert_eq!(
+        rec.timestamp_millis(),
+        Some(1_754_996_229_548 - 8 * 3_600_000)
+    );
+    // into_owned 保留时区
+    assert_eq!(rec.into_owned().utc_offset(), 8 * 3600);
+}
+
+#[test]
+fn out_of_range_fields_yield_none() {
+    for ts in [
+        "2025-13-01 00:00:00.000",
+        "2025-02-29 00:00:00.000",
+        "2025-04-31 00:00:00.000",
+        "2025-01-01 24:00:00.000",
+        "2025-01-01 00:60:00.000",
+    ] {
+        let line = format!("{ts} (EP[0] sess:1 thrd:2 user:a trxid:3 stmt:4 appname:b) SELECT 1");
+        let rec = parse_record(line.as_bytes()).unwrap();
+        assert_eq!(rec.timestamp_millis(), None, "{ts}");
+    }
+}
+
+#[test]
+#[should_panic(expected = "utc_offset")]
+fn utc_offset_rejects_out_of_range() {
+    let _ = LogParser::builder().utc_offset(24 * 3600);
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn chrono_timestamp_keeps_server_offset() {
+    use chrono::{Datelike, Timelike};
+
+    let parser = LogParser::builder()
+        .utc_offset(8 * 3600)
+        .open_bytes(LOG.as_bytes());
+    let ts = parser.iter().next().unwrap().unwrap().timestamp().unwrap();
+    assert_eq!(ts.offset().local_minus_utc(), 8 * 3600);
+    assert_eq!((ts.year(), ts.month(), ts.day()), (2025, 8, 12));
+    assert_eq!((ts.hour(), ts.minute(), ts.second()), (10, 57, 9));
+    assert_eq!(ts.timestamp_subsec_millis(), 548);
+}
+
+#[cfg(feature = "time")]
+#[test]
+fn time_offset_date_time_keeps_server_offset() {
+    let parser = LogParser::builder()
+        .utc_offset(-5 * 3600)
+        .open_bytes(LOG.as_bytes());
+    let ts = parser
+        .iter()
+        .next()
+        .unwrap()
+        .unwrap()
+        .offset_date_time()
+        .unwrap();
+    assert_eq!(ts.offset().whole_seconds(), -5 * 3600);
+    assert_eq!(
+        (ts.year(), ts.month() as u8, ts.day(), ts.hour()),
+        (2025, 8, 12, 10)
+    );
+    assert_eq!(ts.millisecond(), 548);
+    assert_eq!(
+        ts.unix_timestamp_nanos() / 1_000_000,
+        1_754_996_229_548 + 5 * 3_600_000
+    );
+}